@@ -3,12 +3,14 @@ pub mod card_type;
 pub mod card_value;
 pub mod common_card_type;
 
+use serde::{Deserialize, Serialize};
+
 use self::{card_color::CardColor, card_type::CardType, card_value::CardValue};
 
 pub const RED: &str = "[31m";
 pub const RESET: &str = "[0m";
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
 pub struct Card {
     value: CardValue,
     sign: CardType,
@@ -56,6 +58,11 @@ impl Card {
         self.other_can_see_it = value
     }
 
+    /// Returns true if `other` has the same value and sign, whatever their rule and their visibility
+    pub fn same_card(&self, other: &Card) -> bool {
+        self.value == other.value && self.sign == other.sign
+    }
+
     pub fn get_color(&self) -> CardColor {
         match self.sign {
             CardType::Common(ref c) => c.get_color(),
@@ -64,8 +71,24 @@ impl Card {
         }
     }
 
-    /// Returns a card descriptor just valud and sign in one line
-    pub fn to_string_light(&self) -> String {
-        self.value.to_card_string() + " " + &self.sign.to_card_string()
+    /// Returns a compact card descriptor, value and sign without space (`8♥`),
+    /// if `ascii` is true only ASCII characters are used (`8H`)
+    pub fn to_string_light(&self, ascii: bool) -> String {
+        if ascii {
+            self.value.to_ascii_string() + &self.sign.to_ascii_string()
+        } else {
+            self.value.to_card_string() + &self.sign.to_card_string()
+        }
+    }
+
+    /// Returns a card descriptor, value and sign separated by a space (`8 ♥`),
+    /// the sign is colored only if `color` is true
+    pub fn to_string_colored(&self, color: bool) -> String {
+        self.value.to_card_string() + " " + &self.sign.to_card_string_colored(color)
+    }
+
+    /// Returns a card descriptor with only ASCII characters and no escape sequences
+    pub fn to_ascii_string(&self) -> String {
+        self.value.to_ascii_string() + " " + &self.sign.to_ascii_string()
     }
 }
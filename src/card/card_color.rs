@@ -1,4 +1,8 @@
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+use serde::{Deserialize, Serialize};
+
+use super::RED;
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum CardColor {
     Red,
     Black,
@@ -18,3 +22,25 @@ impl std::fmt::Display for CardColor {
         )
     }
 }
+
+impl CardColor {
+    /// Returns the ANSI color sequence (without the escape character) used to render this color,
+    /// [`None`] if the color is rendered with the default color of the terminal
+    ///
+    /// [`CardColor::Undefined`] are looked up by name (`"blue"`, `"green"`, ...), unknown names are not colored
+    pub fn ansi_code(&self) -> Option<&'static str> {
+        match self {
+            CardColor::Red => Some(RED),
+            CardColor::Black => None,
+            CardColor::Undefined(name) => match name.to_lowercase().as_str() {
+                "red" => Some(RED),
+                "green" => Some("[32m"),
+                "yellow" => Some("[33m"),
+                "blue" => Some("[34m"),
+                "magenta" | "purple" => Some("[35m"),
+                "cyan" => Some("[36m"),
+                _ => None,
+            },
+        }
+    }
+}
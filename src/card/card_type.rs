@@ -1,10 +1,12 @@
 use std::str::FromStr;
 
-use crate::card::{RED, RESET};
+use serde::{Deserialize, Serialize};
+
+use crate::card::RESET;
 
 use super::{card_color::CardColor, common_card_type::CommonCardType};
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum CardType {
     Common(CommonCardType),
     Rule,
@@ -13,16 +15,29 @@ pub enum CardType {
 impl CardType {
     // Returns a one or two character string for the card graphics
     pub fn to_card_string(&self) -> String {
+        self.to_card_string_colored(true)
+    }
+
+    /// Same as [`Self::to_card_string`] but without any ANSI escape sequence if `color` is false
+    pub fn to_card_string_colored(&self, color: bool) -> String {
         match self {
-            CardType::Common(color_type) => color_type.to_card_string(),
+            CardType::Common(color_type) => color_type.to_card_string_colored(color),
             CardType::Rule => "♯".to_string(), // trouver un caracter de rêgle (l'outil pour mesurer) à mettre à la place
-            CardType::Jocker { color, .. } => {
-                if color == &CardColor::Red {
-                    format!("\x1b{}\x1b{}", RED, RESET)
-                } else {
-                    "J".to_string()
-                }
-            }
+            CardType::Jocker {
+                color: card_color, ..
+            } => match card_color.ansi_code() {
+                Some(code) if color => format!("\x1b{}J\x1b{}", code, RESET),
+                _ => "J".to_string(),
+            },
+        }
+    }
+
+    /// Returns a one character string for the card graphics using only ASCII characters
+    pub fn to_ascii_string(&self) -> String {
+        match self {
+            CardType::Common(color_type) => color_type.to_ascii_string(),
+            CardType::Rule => "R".to_string(),
+            CardType::Jocker { .. } => "J".to_string(),
         }
     }
 }
@@ -1,6 +1,8 @@
 use std::str::FromStr;
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum CardValue {
     Number(isize),
     MinusInfinity,
@@ -37,6 +39,28 @@ impl CardValue {
             CardValue::PlusInfinity => "+∞".to_string(),
         }
     }
+
+    /// Returns the points of a card left in a hand at the end of a round,
+    /// the infinity cards are not worth any point
+    pub fn point_value(&self) -> isize {
+        match self {
+            CardValue::Number(i) => *i,
+            CardValue::MinusInfinity | CardValue::PlusInfinity => 0,
+        }
+    }
+
+    /// Returns a short string for the card graphics using only ASCII characters (A, J, Q, K for faces)
+    pub fn to_ascii_string(&self) -> String {
+        match self {
+            CardValue::Number(1) => "A".to_string(),
+            CardValue::Number(11) => "J".to_string(),
+            CardValue::Number(12) => "Q".to_string(),
+            CardValue::Number(13) => "K".to_string(),
+            CardValue::Number(i) => format!("{i}"),
+            CardValue::MinusInfinity => "-inf".to_string(),
+            CardValue::PlusInfinity => "+inf".to_string(),
+        }
+    }
 }
 
 impl std::fmt::Display for CardValue {
@@ -1,8 +1,10 @@
 use std::str::FromStr;
 
+use serde::{Deserialize, Serialize};
+
 use super::{card_color::CardColor, RED, RESET};
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum CommonCardType {
     Spade,   // Pique
     Diamond, //Carreau
@@ -50,12 +52,32 @@ impl CommonCardType {
     }
 
     pub fn to_card_string(&self) -> String {
-        // returns a one or two character string for the card graphics
+        self.to_card_string_colored(true)
+    }
+
+    /// Returns a one or two character string for the card graphics, red suits are colored only if `color` is true
+    pub fn to_card_string_colored(&self, color: bool) -> String {
+        let glyph = match self {
+            CommonCardType::Spade => "♤",
+            CommonCardType::Diamond => "♦",
+            CommonCardType::Club => "♧",
+            CommonCardType::Heart => "♥",
+        };
+        if color && self.get_color() == CardColor::Red {
+            format!("\x1b{}{}\x1b{}", RED, glyph, RESET)
+        } else {
+            glyph.to_string()
+        }
+    }
+
+    /// Returns the first letter of the suit
+    pub fn to_ascii_string(&self) -> String {
         match self {
-            CommonCardType::Spade => "♤".to_string(),
-            CommonCardType::Diamond => format!("\x1b{}♦\x1b{}", RED, RESET),
-            CommonCardType::Club => "♧".to_string(),
-            CommonCardType::Heart => format!("\x1b{}♥\x1b{}", RED, RESET),
+            CommonCardType::Spade => "S",
+            CommonCardType::Diamond => "D",
+            CommonCardType::Club => "C",
+            CommonCardType::Heart => "H",
         }
+        .to_string()
     }
 }
@@ -16,11 +16,97 @@ use serde::{
     Deserialize, Deserializer,
 };
 
-#[derive(Default, Clone, Deserialize, Debug)]
+#[derive(Clone, Deserialize, Debug)]
 pub struct Config {
     pub dirname: String,
     #[serde(default)]
     pub cards_effects: HashMap<CardEffectsKey, CardEffects>,
+    /// penalize a player saying something while it is not its turn
+    #[serde(default)]
+    pub penalize_out_of_turn_say: bool,
+    /// the maximum number of cards a player can keep after drawing
+    #[serde(default)]
+    pub max_hand_size: Option<usize>,
+    /// allow a player to play out of turn a card identical to the last played one
+    #[serde(default)]
+    pub allow_jump_in: bool,
+    /// the number of penalities given to a player playing while it is not its turn
+    #[serde(default = "default_penality_count")]
+    pub wrong_turn_penality: usize,
+    /// the number of penalities given to a player playing a card which cannot be placed
+    #[serde(default = "default_penality_count")]
+    pub wrong_card_penality: usize,
+    /// refill the drawable stacks after a draw when they hold less cards than this
+    #[serde(default)]
+    pub refill_threshold: Option<usize>,
+    /// allow a player to pass its turn
+    #[serde(default)]
+    pub allow_pass: bool,
+    /// the number of cards dealt to each player when the game starts
+    #[serde(default = "default_initial_hand_size")]
+    pub initial_hand_size: usize,
+    /// where the card drawn for a common penality goes
+    #[serde(default)]
+    pub penality_target: PenaltyTarget,
+    /// the direction of the turn when a game starts (-1 OR 1)
+    #[serde(default = "default_initial_direction")]
+    pub initial_direction: i8,
+    /// allow the [`CardValue::MinusInfinity`] cards to be played on any card,
+    /// they cannot be played at all otherwise
+    #[serde(default)]
+    pub infinity_wild: bool,
+    /// keep the events removed at the end of each turn inside the event history of the game
+    #[serde(default)]
+    pub retain_events: bool,
+    /// the dealer takes the first turn instead of the player next to them
+    #[serde(default)]
+    pub dealer_plays_first: bool,
+    /// the number of playable stacks created when a game starts
+    #[serde(default = "default_playable_stack_count")]
+    pub playable_stack_count: usize,
+    /// allow a player to play several cards of the same value in one turn
+    #[serde(default)]
+    pub allow_multiple_play: bool,
+}
+
+fn default_penality_count() -> usize {
+    1
+}
+
+fn default_initial_hand_size() -> usize {
+    7
+}
+
+fn default_initial_direction() -> i8 {
+    1
+}
+
+fn default_playable_stack_count() -> usize {
+    1
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            dirname: String::new(),
+            cards_effects: HashMap::new(),
+            penalize_out_of_turn_say: false,
+            max_hand_size: None,
+            allow_jump_in: false,
+            wrong_turn_penality: default_penality_count(),
+            wrong_card_penality: default_penality_count(),
+            refill_threshold: None,
+            allow_pass: false,
+            initial_hand_size: default_initial_hand_size(),
+            penality_target: PenaltyTarget::default(),
+            initial_direction: default_initial_direction(),
+            infinity_wild: false,
+            retain_events: false,
+            dealer_plays_first: false,
+            playable_stack_count: default_playable_stack_count(),
+            allow_multiple_play: false,
+        }
+    }
 }
 
 impl Config {
@@ -54,8 +140,163 @@ impl Config {
                 desc: String::from("Provided path is not a directory"),
             });
         }
+        if self.initial_direction != 1 && self.initial_direction != -1 {
+            return Err(Error::InvalidConfig {
+                desc: format!(
+                    "The initial direction must be 1 or -1 (found {})",
+                    self.initial_direction
+                ),
+            });
+        }
+        if self.playable_stack_count == 0 {
+            return Err(Error::InvalidConfig {
+                desc: String::from("There must be at least one playable stack"),
+            });
+        }
         self.clear();
-        Ok(())
+        self.validate_effects()
+            .map_err(|errors| Error::InvalidConfig {
+                desc: errors
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+            })
+    }
+
+    /// Checks all `cards_effects` and returns all the problems found
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error for each
+    /// + `say` effect without any word
+    /// + empty word to say
+    /// + empty `physical` action name
+    pub fn validate_effects(&self) -> Result<(), Vec<Error>> {
+        let mut errors = Vec::new();
+        for (key, effects) in &self.cards_effects {
+            let effects = match effects {
+                SingOrMult::Single(s) => std::slice::from_ref(s),
+                SingOrMult::Multiple(v) => v.as_slice(),
+            };
+            for effect in effects {
+                let desc = match effect {
+                    SingleCardEffect::PlayerTurnChange(_) => None,
+                    SingleCardEffect::CardPlayerAction(CardPlayerAction::Say(words)) => {
+                        if words.is_empty() {
+                            Some("a say effect has no word to say")
+                        } else if words.iter().any(|word| match word {
+                            SingOrMult::Single(w) => w.is_empty(),
+                            SingOrMult::Multiple(v) => v.iter().any(|w| w.is_empty()),
+                        }) {
+                            Some("a say effect contains an empty word")
+                        } else {
+                            None
+                        }
+                    }
+                    SingleCardEffect::CardPlayerAction(CardPlayerAction::Physical(p)) => p
+                        .is_empty()
+                        .then_some("a physical effect has an empty name"),
+                };
+                if let Some(desc) = desc {
+                    errors.push(Error::InvalidConfig {
+                        desc: format!("Invalid card effect for {key}: {desc}"),
+                    });
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Layers `other` on top of `self`
+    ///
+    /// Merge precedence:
+    /// + `cards_effects`: the effects of both configs are kept, for a key present in both
+    ///   the effects of `self` are applied first, then the ones of `other`
+    /// + `dirname`: replaced by the one of `other` if it is not empty
+    /// + optional values (`max_hand_size`, `refill_threshold`): replaced by the ones of `other` if they are set
+    /// + the other values: replaced by the ones of `other` if they differ from [`Config::default`]
+    pub fn merge(&mut self, other: Config) {
+        // destructured so that a new field cannot be forgotten here
+        let Config {
+            dirname,
+            cards_effects,
+            penalize_out_of_turn_say,
+            max_hand_size,
+            allow_jump_in,
+            wrong_turn_penality,
+            wrong_card_penality,
+            refill_threshold,
+            allow_pass,
+            initial_hand_size,
+            penality_target,
+            initial_direction,
+            infinity_wild,
+            retain_events,
+            dealer_plays_first,
+            playable_stack_count,
+            allow_multiple_play,
+        } = other;
+        let default = Config::default();
+        for (key, effects) in cards_effects {
+            let merged = match self.cards_effects.remove(&key) {
+                Some(base) => merge_card_effects(base, effects),
+                None => effects,
+            };
+            self.cards_effects.insert(key, merged);
+        }
+        if !dirname.is_empty() {
+            self.dirname = dirname;
+        }
+        if max_hand_size.is_some() {
+            self.max_hand_size = max_hand_size;
+        }
+        if refill_threshold.is_some() {
+            self.refill_threshold = refill_threshold;
+        }
+        if penalize_out_of_turn_say != default.penalize_out_of_turn_say {
+            self.penalize_out_of_turn_say = penalize_out_of_turn_say;
+        }
+        if allow_jump_in != default.allow_jump_in {
+            self.allow_jump_in = allow_jump_in;
+        }
+        if wrong_turn_penality != default.wrong_turn_penality {
+            self.wrong_turn_penality = wrong_turn_penality;
+        }
+        if wrong_card_penality != default.wrong_card_penality {
+            self.wrong_card_penality = wrong_card_penality;
+        }
+        if allow_pass != default.allow_pass {
+            self.allow_pass = allow_pass;
+        }
+        if initial_hand_size != default.initial_hand_size {
+            self.initial_hand_size = initial_hand_size;
+        }
+        if penality_target != default.penality_target {
+            self.penality_target = penality_target;
+        }
+        if initial_direction != default.initial_direction {
+            self.initial_direction = initial_direction;
+        }
+        if infinity_wild != default.infinity_wild {
+            self.infinity_wild = infinity_wild;
+        }
+        if retain_events != default.retain_events {
+            self.retain_events = retain_events;
+        }
+        if dealer_plays_first != default.dealer_plays_first {
+            self.dealer_plays_first = dealer_plays_first;
+        }
+        if playable_stack_count != default.playable_stack_count {
+            self.playable_stack_count = playable_stack_count;
+        }
+        if allow_multiple_play != default.allow_multiple_play {
+            self.allow_multiple_play = allow_multiple_play;
+        }
     }
 
     /// Removes unecessary values
@@ -73,6 +314,19 @@ impl Config {
     }
 }
 
+/// Returns the effects of `base` followed by the ones of `other`
+fn merge_card_effects(base: CardEffects, other: CardEffects) -> CardEffects {
+    let mut effects = match base {
+        SingOrMult::Single(s) => vec![s],
+        SingOrMult::Multiple(v) => v,
+    };
+    match other {
+        SingOrMult::Single(s) => effects.push(s),
+        SingOrMult::Multiple(v) => effects.extend(v),
+    }
+    CardEffects::Multiple(effects)
+}
+
 #[derive(Clone, Eq, Hash, PartialEq, Debug)]
 pub struct CardEffectsKey {
     pub c_type: Option<CardType>,
@@ -85,6 +339,17 @@ impl CardEffectsKey {
     }
 }
 
+impl std::fmt::Display for CardEffectsKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.value, &self.c_type) {
+            (Some(value), Some(c_type)) => write!(f, "{value}_{c_type}"),
+            (Some(value), None) => write!(f, "{value}"),
+            (None, Some(c_type)) => write!(f, "{c_type}"),
+            (None, None) => write!(f, "any"),
+        }
+    }
+}
+
 impl FromStr for CardEffectsKey {
     type Err = anyhow::Error;
 
@@ -147,6 +412,31 @@ where
     Multiple(Vec<T>),
 }
 
+impl<T> SingOrMult<T>
+where
+    T: std::fmt::Debug + Clone,
+{
+    /// Returns the value at `index`, a [`SingOrMult::Single`] only holds the index 0
+    pub fn get(&self, index: usize) -> Option<&T> {
+        match self {
+            SingOrMult::Single(s) => (index == 0).then_some(s),
+            SingOrMult::Multiple(v) => v.get(index),
+        }
+    }
+}
+
+/// The destination of the card drawn when a player takes a penality
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum PenaltyTarget {
+    /// the card goes into the hand of the penalized player
+    #[default]
+    #[serde(alias = "hand")]
+    Hand,
+    /// the card goes on the [`Stack`](crate::stack::Stack) at this index
+    #[serde(alias = "stack")]
+    Stack(usize),
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(tag = "type", content = "values")]
 pub enum CardPlayerAction {
@@ -259,6 +549,8 @@ impl FromStr for SingleCardEffect {
     }
 }
 
+/// The effects of a card, they are applied in insertion order
+/// ([`Config::merge`] appends the effects of the merged config after the existing ones)
 pub type CardEffects = SingOrMult<SingleCardEffect>;
 
 struct CardEffectsVisitor;
@@ -0,0 +1,72 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// The key of the data stored by a rule (the name of the rule)
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct DataKey(pub String);
+
+/// Raw data stored by a rule
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+pub struct DataStorageType(pub Vec<u8>);
+
+impl DataStorageType {
+    /// Stores `v` as little-endian bytes
+    pub fn set_u64(&mut self, v: u64) {
+        self.0 = v.to_le_bytes().to_vec();
+    }
+
+    /// Reads the stored bytes as a little-endian `u64`, `None` if they are not 8 bytes long
+    pub fn get_u64(&self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.0.as_slice().try_into().ok()?))
+    }
+
+    /// Stores `s` as utf-8 bytes
+    pub fn set_string(&mut self, s: &str) {
+        self.0 = s.as_bytes().to_vec();
+    }
+
+    /// Reads the stored bytes as a string, `None` if they are not valid utf-8
+    pub fn get_string(&self) -> Option<String> {
+        String::from_utf8(self.0.clone()).ok()
+    }
+
+    /// Stores a copy of `bytes`
+    pub fn set_bytes(&mut self, bytes: &[u8]) {
+        self.0 = bytes.to_vec();
+    }
+
+    /// Returns the stored bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// All the data stored by the rules inside a [`DataContainer`]
+pub type Data = BTreeMap<DataKey, DataStorageType>;
+
+/// A structure inside which rules can store their own data
+pub trait DataContainer {
+    fn get_data(&self) -> &Data;
+    fn get_data_mut(&mut self) -> &mut Data;
+
+    /// Returns the data of the rule `rule_name` if it has stored some
+    fn get_rule_data(&self, rule_name: &str) -> Option<&DataStorageType> {
+        self.get_data().get(&DataKey(rule_name.to_owned()))
+    }
+
+    /// Returns the data of the rule `rule_name`, inserting an empty one if absent
+    fn get_or_insert_rule_data(&mut self, rule_name: &str) -> &mut DataStorageType {
+        self.get_data_mut()
+            .entry(DataKey(rule_name.to_owned()))
+            .or_default()
+    }
+
+    /// Calls `f` on the data of the rule `rule_name`, inserting an empty one if absent
+    fn mutate_rule_data<F>(&mut self, rule_name: &str, f: F)
+    where
+        F: FnOnce(&mut DataStorageType),
+    {
+        f(self.get_or_insert_rule_data(rule_name))
+    }
+}
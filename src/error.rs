@@ -1,4 +1,4 @@
-use crate::{mao::automaton::PlayerAction, stack::stack_type::StackType};
+use crate::{card::Card, mao::automaton::PlayerAction, stack::stack_type::StackType};
 
 #[derive(Debug)]
 pub struct DmDescription(pub(crate) String);
@@ -9,6 +9,7 @@ pub enum Error {
         desc: DmDescription,
     },
     NotEnoughCardsForInitilization,
+    EmptyDeck,
     InvalidConfig {
         desc: String,
     },
@@ -54,6 +55,8 @@ pub enum Error {
     InvalidMaoInteraction {
         expected: Vec<PlayerAction>,
         received: Vec<PlayerAction>,
+        /// the index of the first action which differs
+        mismatch_at: usize,
     },
     OnMaoInteraction(String),
     RuleAlreadyActivated {
@@ -62,7 +65,28 @@ pub enum Error {
     RuleNotActivated {
         rule_name: String,
     },
+    RuleVetoed {
+        rule_name: String,
+        reason: String,
+    },
     InvalidExpectingValue(String),
+    DisabledByConfig {
+        feature: String,
+    },
+    InvalidAutomaton {
+        desc: String,
+    },
+    NothingToUndo,
+    AmbiguousStack {
+        stack_type: StackType,
+        count: usize,
+    },
+    CardMismatch {
+        card_index: usize,
+        /// the card held by the player
+        expected: Box<Card>,
+        received: Box<Card>,
+    },
 }
 
 impl Error {
@@ -90,6 +114,7 @@ impl std::fmt::Display for Error {
             Error::NotEnoughCardsForInitilization => {
                 write!(f, "There is not enough cards in the stack")
             }
+            Error::EmptyDeck => write!(f, "The deck does not hold any card"),
             Error::InvalidConfig { ref desc } => write!(f, "{}", desc),
             Error::DlOpen2 { desc } => write!(f, "{}", desc.0),
             Error::IOError { error } => write!(f, "{}", error),
@@ -131,9 +156,13 @@ impl std::fmt::Display for Error {
                 keys.join(", ")
             ),
             Error::InvalidRequestResponse => write!(f, "Invalid Request Reponse"),
-            Error::InvalidMaoInteraction { expected, received } => write!(
+            Error::InvalidMaoInteraction {
+                expected,
+                received,
+                mismatch_at,
+            } => write!(
                 f,
-                "Invalid mao interactions: \nexpected: {expected:?}\nreceived: {received:?}"
+                "Invalid mao interactions (mismatch at {mismatch_at}): \nexpected: {expected:?}\nreceived: {received:?}"
             ),
             Error::OnMaoInteraction(s) => write!(f, "OnMaoInteraction: {}", s),
             Error::InvalidRuleIndex { rule_index, len } => write!(
@@ -151,7 +180,31 @@ impl std::fmt::Display for Error {
                     rule_name
                 )
             }
+            Error::RuleVetoed { rule_name, reason } => {
+                write!(f, "The {} cannot be activated ({})", rule_name, reason)
+            }
             Error::InvalidExpectingValue(s) => f.write_str(s),
+            Error::DisabledByConfig { feature } => {
+                write!(f, "{} is disabled by the configuration", feature)
+            }
+            Error::InvalidAutomaton { desc } => write!(f, "Invalid automaton: {}", desc),
+            Error::NothingToUndo => write!(f, "There is nothing to undo"),
+            Error::AmbiguousStack { stack_type, count } => write!(
+                f,
+                "Expected a single {} stack but there are {}",
+                stack_type, count
+            ),
+            Error::CardMismatch {
+                card_index,
+                expected,
+                received,
+            } => write!(
+                f,
+                "The card at index {} is {} and not {}",
+                card_index,
+                expected.to_string_light(false),
+                received.to_string_light(false)
+            ),
         }
     }
 }
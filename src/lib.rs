@@ -1,5 +1,6 @@
 pub mod card;
 pub mod config;
+pub mod data;
 pub mod error;
 pub mod mao;
 pub mod mao_event;
@@ -12,7 +13,126 @@ pub const VERSION: &str = "1.0";
 #[cfg(test)]
 mod test {
     use super::mao::automaton::*;
-    use crate::mao::mao_action::MaoInteraction;
+    use std::collections::HashMap;
+
+    use crate::{
+        card::{
+            card_color::CardColor, card_type::CardType, card_value::CardValue,
+            common_card_type::CommonCardType, Card,
+        },
+        config::{
+            CardEffectsKey, CardPlayerAction, Config, PenaltyTarget, SingOrMult, SingleCardEffect,
+        },
+        data::{DataContainer, DataStorageType},
+        error::Error,
+        mao::{
+            mao_action::{IdString, MaoInteraction},
+            mao_core::{MaoCore, PlayerTurnChange, PlayerTurnResult, PlayerTurnUpdater},
+        },
+        mao_event::{
+            card_event::CardEvent,
+            mao_event_result::{
+                Disallow, GivePenality, MaoEventResult, MaoEventResultType, Necessary,
+                WrongPlayerInteraction,
+            },
+            MaoEvent, StackTarget,
+        },
+        player::Player,
+        rule::Rule,
+        stack::{stack_property::StackProperty, stack_type::StackType, Stack},
+    };
+
+    fn card(value: isize, sign: CommonCardType) -> Card {
+        Card::new(CardValue::Number(value), CardType::Common(sign), None)
+    }
+
+    /// The result of a test rule refusing an event for `reason`
+    fn disallow(reason: &str) -> anyhow::Result<MaoEventResult> {
+        Ok(MaoEventResult::new(
+            Necessary::BasicRule(true),
+            MaoEventResultType::Disallow(Disallow::new(
+                String::from("test"),
+                reason.to_owned(),
+                None,
+            )),
+        ))
+    }
+
+    fn ignore() -> anyhow::Result<MaoEventResult> {
+        Ok(MaoEventResult::new(
+            Necessary::BasicRule(false),
+            MaoEventResultType::Ignored,
+        ))
+    }
+
+    /// A rule ignoring every event
+    fn ignore_all(_: &MaoEvent, _: &mut MaoCore) -> anyhow::Result<MaoEventResult> {
+        ignore()
+    }
+
+    /// A rule refusing the cards of value 3
+    fn refuse_threes(event: &MaoEvent, _: &mut MaoCore) -> anyhow::Result<MaoEventResult> {
+        match event {
+            MaoEvent::PlayedCardEvent(card_event)
+                if card_event.played_card.get_value() == &CardValue::Number(3) =>
+            {
+                disallow("no three")
+            }
+            _ => ignore(),
+        }
+    }
+
+    /// A rule refusing every physical action
+    fn refuse_physicals(event: &MaoEvent, _: &mut MaoCore) -> anyhow::Result<MaoEventResult> {
+        match event {
+            MaoEvent::PhysicalEvent { .. } => disallow("no moving"),
+            _ => ignore(),
+        }
+    }
+
+    /// A rule incompatible with the rule `second`, it also complains when a rule is deactivated
+    fn refuse_second(event: &MaoEvent, _: &mut MaoCore) -> anyhow::Result<MaoEventResult> {
+        match event {
+            MaoEvent::RuleActivated { rule_name } if rule_name == "second" => {
+                disallow("incompatible")
+            }
+            MaoEvent::RuleDeactivated { .. } => disallow("keep it"),
+            _ => ignore(),
+        }
+    }
+
+    /// A rule complaining about every refill, which only counts its fires
+    fn watch_refills(event: &MaoEvent, _: &mut MaoCore) -> anyhow::Result<MaoEventResult> {
+        match event {
+            MaoEvent::StackRefilled { .. } => disallow("refilled"),
+            _ => ignore(),
+        }
+    }
+
+    /// A rule refusing every message
+    fn refuse_says(event: &MaoEvent, _: &mut MaoCore) -> anyhow::Result<MaoEventResult> {
+        match event {
+            MaoEvent::SayEvent { .. } => disallow("silence"),
+            _ => ignore(),
+        }
+    }
+
+    fn mao_from(players: Vec<Player>, stacks: Vec<Stack>) -> MaoCore {
+        MaoCore::new(
+            Vec::new(),
+            stacks,
+            players,
+            Automaton::from_iter(Vec::<Vec<NodeState>>::new()),
+        )
+    }
+
+    /// Creates a [`MaoCore`] with the default actions and without any rule
+    fn mao_from_config(test_name: &str, mut config: Config) -> MaoCore {
+        let dir = std::env::temp_dir().join(format!("mao_core_{}", test_name));
+        std::fs::create_dir_all(&dir).unwrap();
+        config.dirname = dir.to_string_lossy().into_owned();
+        MaoCore::from_config(&mut config).unwrap()
+    }
 
     fn generate_path() -> Vec<Vec<NodeState>> {
         vec![
@@ -84,7 +204,7 @@ mod test {
         let mut other_act = initial_actions.to_owned();
         other_act.extend(actions_to_add());
         let mut other_auto = Automaton::from_iter(&mut other_act);
-        other_auto.remove_paths(&mut actions_to_add());
+        assert_eq!(other_auto.remove_paths(&mut actions_to_add()), 3);
 
         assert_eq!(init_auto, other_auto);
     }
@@ -100,4 +220,3181 @@ mod test {
 
         assert_eq!(init_auto, inv_auto);
     }
+
+    #[test]
+    fn structurally_eq_automaton() {
+        let mut tagged_actions = generate_path();
+        for path in tagged_actions.iter_mut() {
+            path.last_mut().unwrap().rule = Some(String::from("tagged"));
+        }
+
+        let init_auto = Automaton::from_iter(generate_path());
+        let tagged_auto = Automaton::from_iter(tagged_actions);
+
+        assert_ne!(init_auto, tagged_auto);
+        assert!(init_auto.structurally_eq(&tagged_auto));
+        assert!(!init_auto.structurally_eq(&Automaton::from_iter(actions_to_add())));
+    }
+
+    #[test]
+    fn path_exists_full() {
+        let auto = Automaton::from_iter(generate_path());
+
+        assert!(
+            auto.path_exists_full(&[PlayerAction::SelectCard, PlayerAction::SelectPlayableStack])
+        );
+        assert!(auto.path_exists(&[PlayerAction::SelectDrawableStack]));
+        // the prefix exists but not the leaf
+        assert!(
+            !auto.path_exists_full(&[PlayerAction::SelectCard, PlayerAction::SelectDrawableStack])
+        );
+        assert!(!auto.path_exists(&[PlayerAction::SelectCard]));
+    }
+
+    #[test]
+    fn paths_for_rule() {
+        let mut auto = Automaton::from_iter(generate_path());
+        auto.extend(actions_to_add());
+
+        let mut expected = actions_to_add();
+        for path in expected.iter_mut() {
+            for node_state in path.split_last_mut().unwrap().1 {
+                node_state.rule = None;
+            }
+        }
+
+        assert_eq!(auto.paths_for_rule("actions to add"), expected);
+        assert!(auto.paths_for_rule("unknown").is_empty());
+    }
+
+    #[test]
+    fn top_playable_cards() {
+        let mao = mao_from(
+            vec![],
+            vec![
+                Stack::new(vec![], false, vec![StackType::Drawable]),
+                Stack::new(
+                    vec![
+                        card(3, CommonCardType::Club),
+                        card(5, CommonCardType::Heart),
+                    ],
+                    true,
+                    vec![StackType::Playable],
+                ),
+                Stack::new(vec![], true, vec![StackType::Discardable]),
+                Stack::new(
+                    vec![card(12, CommonCardType::Spade)],
+                    true,
+                    vec![StackType::Playable],
+                ),
+            ],
+        );
+
+        assert_eq!(
+            mao.top_playable_cards(),
+            vec![
+                (1, Some(&card(5, CommonCardType::Heart))),
+                (3, Some(&card(12, CommonCardType::Spade)))
+            ]
+        );
+    }
+
+    #[test]
+    fn default_discard_target() {
+        let mut mao = mao_from(vec![], MaoCore::init_stacks().unwrap());
+
+        assert_eq!(mao.default_discard_target(), Some(2));
+        assert_eq!(mao.get_discardable_stacks().len(), 1);
+        assert_eq!(mao.get_discardable_stacks_mut().first().unwrap().0, 2);
+    }
+
+    #[test]
+    fn rule_card_needs_activated_rule() {
+        let mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new("p2".to_owned(), vec![]),
+            ],
+            MaoCore::init_stacks().unwrap(),
+        );
+        let stack = &mao.stacks()[1];
+        let top = stack.top().unwrap().to_owned();
+        let rule_card = Card::new(
+            top.get_value().to_owned(),
+            top.get_sign().to_owned(),
+            Some("some_rule".to_owned()),
+        );
+
+        assert!(!mao.is_rule_activated("some_rule"));
+        assert!(matches!(
+            mao.can_play(mao.player_turn(), &rule_card, Some(stack)),
+            PlayerTurnResult::Other { .. }
+        ));
+        assert_eq!(
+            mao.can_play(mao.player_turn(), &top, Some(stack)),
+            PlayerTurnResult::CanPlay
+        );
+    }
+
+    #[test]
+    fn events_for_player() {
+        let mut mao = mao_from(vec![], MaoCore::init_stacks().unwrap());
+        let events = vec![
+            MaoEvent::SayEvent {
+                message: "hello".to_owned(),
+                player_index: 0,
+            },
+            MaoEvent::PlayedCardEvent(CardEvent::new(
+                card(4, CommonCardType::Diamond),
+                1,
+                Some(1),
+                0,
+            )),
+            MaoEvent::PhysicalEvent {
+                physical_name: "punch".to_owned(),
+                player_index: 1,
+            },
+            MaoEvent::GameStart,
+        ];
+        for event in &events {
+            mao.on_event(event).unwrap();
+        }
+
+        assert_eq!(mao.events_for_player(0), vec![&events[0]]);
+        assert_eq!(mao.events_for_player(1), vec![&events[1], &events[2]]);
+        mao.clear_player_events();
+        assert!(mao.players_events().is_empty());
+    }
+
+    #[test]
+    fn remove_first_matching() {
+        let mut player = Player::new(
+            "p1".to_owned(),
+            vec![
+                card(2, CommonCardType::Spade),
+                card(7, CommonCardType::Heart),
+                card(7, CommonCardType::Club),
+            ],
+        );
+
+        assert_eq!(
+            player.remove_first_matching(&|c| c.get_value() == &CardValue::Number(7)),
+            Some(card(7, CommonCardType::Heart))
+        );
+        assert_eq!(
+            player.remove_first_matching(&|c| c.get_value() == &CardValue::Number(10)),
+            None
+        );
+        assert_eq!(
+            player.get_cards(),
+            &[
+                card(2, CommonCardType::Spade),
+                card(7, CommonCardType::Club)
+            ]
+        );
+    }
+
+    #[test]
+    fn card_ascii_rendering() {
+        let heart = card(12, CommonCardType::Heart);
+
+        assert_eq!(heart.to_ascii_string(), "Q H");
+        assert_eq!(card(7, CommonCardType::Club).to_ascii_string(), "7 C");
+        assert!(!heart.to_ascii_string().contains('\x1b'));
+        assert!(!heart.to_string_colored(false).contains('\x1b'));
+        assert!(heart.to_string_colored(true).contains('\x1b'));
+    }
+
+    #[test]
+    fn card_light_rendering() {
+        assert_eq!(card(8, CommonCardType::Spade).to_string_light(false), "8♤");
+        assert_eq!(card(8, CommonCardType::Heart).to_string_light(true), "8H");
+        assert_eq!(card(1, CommonCardType::Club).to_string_light(true), "AC");
+        assert_eq!(
+            card(10, CommonCardType::Diamond).to_string_light(true),
+            "10D"
+        );
+        assert_eq!(
+            Card::new(CardValue::PlusInfinity, CardType::Rule, None).to_string_light(false),
+            "+∞♯"
+        );
+    }
+
+    #[test]
+    fn mao_core_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<MaoCore>();
+    }
+
+    #[test]
+    fn validate_config_effects() {
+        let mut config = Config {
+            dirname: String::new(),
+            cards_effects: HashMap::from([
+                (
+                    CardEffectsKey::new(None, Some(CardValue::Number(8))),
+                    SingOrMult::Multiple(vec![
+                        SingleCardEffect::CardPlayerAction(CardPlayerAction::Physical(
+                            String::new(),
+                        )),
+                        SingleCardEffect::CardPlayerAction(CardPlayerAction::Say(vec![])),
+                    ]),
+                ),
+                (
+                    CardEffectsKey::new(Some(CardType::Common(CommonCardType::Heart)), None),
+                    SingOrMult::Single(SingleCardEffect::CardPlayerAction(CardPlayerAction::Say(
+                        vec![SingOrMult::Single("heart".to_owned())],
+                    ))),
+                ),
+            ]),
+            ..Default::default()
+        };
+
+        assert_eq!(config.validate_effects().unwrap_err().len(), 2);
+        config
+            .cards_effects
+            .remove(&CardEffectsKey::new(None, Some(CardValue::Number(8))));
+        assert!(config.validate_effects().is_ok());
+    }
+
+    #[test]
+    fn refresh_possible_actions() {
+        let mut mao = mao_from(vec![], MaoCore::init_stacks().unwrap());
+        assert!(mao.possible_actions().is_empty());
+
+        let mut config = mao.config().to_owned();
+        config.cards_effects.insert(
+            CardEffectsKey::new(None, Some(CardValue::Number(5))),
+            SingOrMult::Single(SingleCardEffect::CardPlayerAction(
+                CardPlayerAction::Physical("cry".to_owned()),
+            )),
+        );
+        mao.set_config(config);
+
+        assert_eq!(mao.possible_actions(), &["cry".to_owned()]);
+    }
+
+    #[test]
+    fn draw_specific_card() {
+        let mut mao = mao_from(
+            vec![Player::new("p1".to_owned(), vec![])],
+            vec![Stack::new(
+                vec![
+                    card(3, CommonCardType::Spade),
+                    card(9, CommonCardType::Heart),
+                    card(1, CommonCardType::Club),
+                ],
+                false,
+                vec![StackType::Drawable],
+            )],
+        );
+
+        let drawn = mao
+            .draw_specific_card(0, 0, |c| c.get_color() == CardColor::Red)
+            .unwrap();
+        assert_eq!(drawn, card(9, CommonCardType::Heart));
+        assert_eq!(mao.players()[0].get_cards(), &[drawn]);
+        assert_eq!(mao.stacks()[0].len(), 2);
+        assert!(matches!(
+            mao.draw_specific_card(0, 0, |c| c.get_color() == CardColor::Red),
+            Err(Error::NotEnoughCards)
+        ));
+    }
+
+    #[test]
+    fn serialize_player_data() {
+        let mut player = Player::new("p1".to_owned(), vec![card(4, CommonCardType::Club)]);
+        player.mutate_rule_data("counter", |data| data.0.push(3));
+
+        let restored: Player =
+            serde_json::from_str(&serde_json::to_string(&player).unwrap()).unwrap();
+
+        assert_eq!(restored, player);
+        assert_eq!(restored.get_rule_data("counter").unwrap().0, vec![3]);
+    }
+
+    #[test]
+    fn game_data_is_cleared_on_new_game() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new("p2".to_owned(), vec![]),
+            ],
+            MaoCore::init_stacks().unwrap(),
+        );
+        mao.mutate_rule_data("drawn", |data| data.0.push(1));
+        mao.mutate_rule_data("drawn", |data| data.0.push(2));
+        assert_eq!(mao.get_rule_data("drawn").unwrap().0, vec![1, 2]);
+
+        mao.init_new_game(2).unwrap();
+        assert!(mao.get_rule_data("drawn").is_none());
+    }
+
+    #[test]
+    fn data_storage_typed_accessors() {
+        let mut data = DataStorageType::default();
+        assert_eq!(data.get_u64(), None);
+        data.set_u64(42);
+        assert_eq!(data.get_u64(), Some(42));
+
+        data.set_string("hello");
+        assert_eq!(data.get_string(), Some("hello".to_owned()));
+        assert_eq!(data.get_u64(), None);
+
+        data.set_bytes(&[0xff, 0xfe]);
+        assert_eq!(data.as_bytes(), &[0xff, 0xfe]);
+        assert_eq!(data.get_string(), None);
+    }
+
+    #[test]
+    fn set_player_turn() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new("p2".to_owned(), vec![]),
+                Player::new("p3".to_owned(), vec![]),
+            ],
+            MaoCore::init_stacks().unwrap(),
+        );
+        assert!(mao.set_player_turn(2).is_ok());
+        assert_eq!(mao.player_turn(), 2);
+        assert!(matches!(
+            mao.set_player_turn(3),
+            Err(Error::InvalidPlayerIndex {
+                player_index: 3,
+                len: 3
+            })
+        ));
+        assert_eq!(mao.player_turn(), 2);
+    }
+
+    #[test]
+    fn say_action_without_rules() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![card(3, CommonCardType::Heart)]),
+                Player::new("p2".to_owned(), vec![]),
+            ],
+            MaoCore::init_stacks().unwrap(),
+        );
+        let res = mao.on_say_action(0, "hello".to_owned()).unwrap();
+        assert!(res.is_empty());
+        // no penality has been given
+        assert_eq!(mao.players()[0].get_cards().len(), 1);
+    }
+
+    #[test]
+    fn say_action_disallowed() {
+        let mut mao = MaoCore::new(
+            vec![Rule::from_fn("silence", refuse_says)],
+            MaoCore::init_stacks().unwrap(),
+            vec![
+                Player::new("p1".to_owned(), vec![card(3, CommonCardType::Heart)]),
+                Player::new("p2".to_owned(), vec![]),
+            ],
+            Automaton::from_iter(Vec::<Vec<NodeState>>::new()),
+        );
+        mao.activate_rule("silence").unwrap();
+        let res = mao.on_say_action(1, "hello".to_owned()).unwrap();
+        assert_eq!(res.len(), 1);
+        assert!(matches!(&res[0], WrongPlayerInteraction::Disallow(d) if d.msg == "silence"));
+        assert_eq!(mao.players()[1].get_cards().len(), 1);
+        // saying does not change the turn
+        assert_eq!(mao.player_turn(), 1);
+    }
+
+    #[test]
+    fn penalize_out_of_turn_say() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![card(3, CommonCardType::Heart)]),
+                Player::new("p2".to_owned(), vec![]),
+            ],
+            MaoCore::init_stacks().unwrap(),
+        );
+        assert_eq!(mao.player_turn(), 1);
+        assert!(mao.on_say_action(0, "hello".to_owned()).unwrap().is_empty());
+        assert_eq!(mao.players()[0].get_cards().len(), 1);
+
+        let mut config = mao.config().to_owned();
+        config.penalize_out_of_turn_say = true;
+        mao.set_config(config);
+
+        // the current player can still speak
+        assert!(mao.on_say_action(1, "hello".to_owned()).unwrap().is_empty());
+        assert!(mao.players()[1].get_cards().is_empty());
+
+        let res = mao.on_say_action(0, "hello".to_owned()).unwrap();
+        assert!(matches!(
+            res.as_slice(),
+            [WrongPlayerInteraction::Disallow(_)]
+        ));
+        assert_eq!(mao.players()[0].get_cards().len(), 2);
+    }
+
+    #[test]
+    fn peek_next_player() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new("p2".to_owned(), vec![]),
+                Player::new("p3".to_owned(), vec![]),
+            ],
+            MaoCore::init_stacks().unwrap(),
+        );
+        let changes = [
+            PlayerTurnChange::default(),
+            PlayerTurnChange::Rotate(PlayerTurnUpdater::Update(1)),
+            PlayerTurnChange::Update(PlayerTurnUpdater::Update(-4)),
+            PlayerTurnChange::Rotate(PlayerTurnUpdater::Set(0)),
+        ];
+        for change in changes {
+            let peeked = mao.peek_next_player(&change);
+            let player_turn = mao.player_turn();
+            assert_eq!(mao.peek_next_player(&change), peeked);
+            assert_eq!(mao.player_turn(), player_turn);
+            mao.update_turn(change);
+            assert_eq!(mao.player_turn(), peeked);
+        }
+    }
+
+    #[test]
+    fn activate_unknown_rules() {
+        let mut mao = mao_from(vec![], MaoCore::init_stacks().unwrap());
+        let errors = mao.activate_rules(&["first", "second"]).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(errors
+            .iter()
+            .all(|err| matches!(err, Error::RuleNotFound { .. })));
+        assert!(mao.activated_rules_indexes().is_empty());
+        assert!(mao.activate_rules(&[]).is_ok());
+    }
+
+    #[test]
+    fn activate_several_rules() {
+        let mut mao = MaoCore::new(
+            vec![
+                Rule::from_fn("first", ignore_all),
+                Rule::from_fn("second", ignore_all),
+            ],
+            MaoCore::init_stacks().unwrap(),
+            vec![],
+            Automaton::from_iter(Vec::<Vec<NodeState>>::new()),
+        );
+        let errors = mao
+            .activate_rules(&["first", "unknown", "second"])
+            .unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], Error::RuleNotFound { .. }));
+        assert!(mao.is_rule_activated("first"));
+        assert!(mao.is_rule_activated("second"));
+
+        // the already activated rules are reported too
+        let errors = mao.activate_rules(&["second"]).unwrap_err();
+        assert!(matches!(errors[0], Error::RuleAlreadyActivated { .. }));
+        assert_eq!(mao.activated_rules_indexes(), &[0, 1]);
+    }
+
+    #[test]
+    fn fuzzy_rule_name() {
+        let mut names = ["libSevenSay", "Seven", "SevenSay", "Eight"];
+        names.sort_by_key(|name| MaoCore::fuzzy_match_score(name, "seven"));
+        let scores: Vec<Option<usize>> = names
+            .iter()
+            .map(|name| MaoCore::fuzzy_match_score(name, "seven"))
+            .collect();
+        // no match first
+        assert_eq!(names, ["Eight", "Seven", "SevenSay", "libSevenSay"]);
+        assert_eq!(scores, [None, Some(0), Some(1), Some(8)]);
+        assert!(mao_from(vec![], MaoCore::init_stacks().unwrap())
+            .find_rule_fuzzy("seven")
+            .is_empty());
+    }
+
+    #[test]
+    fn add_player() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new("p2".to_owned(), vec![]),
+            ],
+            MaoCore::init_stacks().unwrap(),
+        );
+        let nb_drawable = mao.stacks()[0].get_cards().len();
+        assert_eq!(mao.add_player("p3".to_owned(), 5).unwrap(), 2);
+        assert_eq!(mao.players().len(), 3);
+        assert_eq!(mao.players()[2].get_pseudo(), "p3");
+        assert_eq!(mao.players()[2].get_cards().len(), 5);
+        assert_eq!(mao.stacks()[0].get_cards().len(), nb_drawable - 5);
+        // joining is not an action done during a turn
+        assert!(mao.players_events().is_empty());
+
+        assert!(matches!(
+            mao.add_player("p4".to_owned(), 1000),
+            Err(Error::NotEnoughCards)
+        ));
+        assert_eq!(mao.players().len(), 3);
+        assert_eq!(
+            mao.stacks()
+                .iter()
+                .map(|stack| stack.get_cards().len())
+                .sum::<usize>(),
+            nb_drawable - 5 + 1
+        );
+    }
+
+    #[test]
+    fn remove_player() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new(
+                    "p2".to_owned(),
+                    vec![card(2, CommonCardType::Club), card(3, CommonCardType::Club)],
+                ),
+                Player::new("p3".to_owned(), vec![]),
+            ],
+            MaoCore::init_stacks().unwrap(),
+        );
+        mao.set_player_turn(2).unwrap();
+        assert!(mao
+            .remove_player(1, StackTarget::Stack(mao.stacks().len()))
+            .is_err());
+        assert_eq!(mao.players().len(), 3);
+
+        let player = mao.remove_player(1, StackTarget::Stack(2)).unwrap();
+        assert_eq!(player.get_pseudo(), "p2");
+        assert!(player.get_cards().is_empty());
+        assert_eq!(mao.stacks()[2].get_cards().len(), 2);
+        assert_eq!(mao.players().len(), 2);
+        assert_eq!(mao.players()[mao.player_turn()].get_pseudo(), "p3");
+        assert_eq!(mao.dealer(), 0);
+
+        // the removed player was playing, the next one plays
+        mao.remove_player(1, StackTarget::Player(0)).unwrap();
+        assert_eq!(mao.player_turn(), 0);
+        assert!(mao.player_turn() < mao.players().len());
+    }
+
+    #[test]
+    fn draw_over_max_hand_size() {
+        let mut mao = mao_from_config(
+            "draw_over_max_hand_size",
+            Config {
+                max_hand_size: Some(5),
+                ..Default::default()
+            },
+        );
+        let hand: Vec<Card> = (2..=6).map(|v| card(v, CommonCardType::Spade)).collect();
+        mao.players_mut().extend([
+            Player::new("p1".to_owned(), vec![]),
+            Player::new("p2".to_owned(), hand.to_owned()),
+        ]);
+
+        let MaoInteractionResult::Leaf { interactions, func } =
+            mao.on_action(MaoInteraction::new(None, PlayerAction::SelectDrawableStack))
+        else {
+            panic!("drawing should be a leaf");
+        };
+        assert!(func(1, &mut mao, &interactions).unwrap().is_empty());
+
+        // the drawn card has been discarded
+        assert_eq!(mao.players()[1].get_cards(), hand.as_slice());
+        assert_eq!(mao.stacks()[2].get_cards().len(), 1);
+        assert_eq!(mao.player_turn(), 0);
+    }
+
+    #[test]
+    fn stack_property_len() {
+        let mut player = Player::new("p1".to_owned(), vec![]);
+        assert!(player.is_empty());
+        assert_eq!(player.len(), 0);
+        player.add_card(card(1, CommonCardType::Heart));
+        assert!(!player.is_empty());
+        assert_eq!(player.len(), 1);
+
+        let stack = Stack::new(
+            vec![
+                card(1, CommonCardType::Heart),
+                card(2, CommonCardType::Heart),
+            ],
+            true,
+            vec![StackType::Playable],
+        );
+        assert!(!StackProperty::is_empty(&stack));
+        assert_eq!(StackProperty::len(&stack), 2);
+    }
+
+    #[test]
+    fn discard_from_hand() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new(
+                    "p2".to_owned(),
+                    vec![
+                        card(4, CommonCardType::Club),
+                        card(7, CommonCardType::Heart),
+                    ],
+                ),
+            ],
+            MaoCore::init_stacks().unwrap(),
+        );
+        assert!(mao.discard_from_hand(1, 2, None).is_err());
+        // the playable stack is not discardable
+        assert!(mao.discard_from_hand(1, 0, Some(1)).is_err());
+
+        assert!(mao.discard_from_hand(1, 1, None).unwrap().is_empty());
+        assert_eq!(
+            mao.players()[1].get_cards(),
+            &[card(4, CommonCardType::Club)]
+        );
+        assert_eq!(
+            mao.stacks()[2].get_cards(),
+            &[card(7, CommonCardType::Heart)]
+        );
+        assert_eq!(mao.player_turn(), 0);
+    }
+
+    #[test]
+    fn penalty_on_wrong_turn_play() {
+        let mut mao = mao_from_config("penalty_on_wrong_turn_play", Config::default());
+        mao.players_mut().extend([
+            Player::new("p1".to_owned(), vec![card(3, CommonCardType::Diamond)]),
+            Player::new("p2".to_owned(), vec![]),
+        ]);
+        assert!(matches!(
+            mao.on_action(MaoInteraction::new(
+                Some(IdString::Index(0)),
+                PlayerAction::SelectCard
+            )),
+            MaoInteractionResult::AdvancedNextState
+        ));
+        let MaoInteractionResult::Leaf { interactions, func } = mao.on_action(MaoInteraction::new(
+            Some(IdString::Index(1)),
+            PlayerAction::SelectPlayableStack,
+        )) else {
+            panic!("playing should be a leaf");
+        };
+        let res = func(0, &mut mao, &interactions).unwrap();
+        assert!(matches!(
+            res.as_slice(),
+            [WrongPlayerInteraction::Disallow(_)]
+        ));
+        // the card has not been played and a penality card has been drawn
+        assert_eq!(mao.players()[0].len(), 2);
+        assert!(!mao
+            .players_events()
+            .iter()
+            .any(|event| matches!(event, MaoEvent::PenaltyApplied { .. })));
+    }
+
+    #[test]
+    fn action_requires_rule() {
+        let mut actions = generate_path();
+        actions.extend(actions_to_add());
+        let auto = Automaton::from_iter(actions);
+
+        assert_eq!(
+            auto.action_requires_rule(&[
+                PlayerAction::SelectCard,
+                PlayerAction::SelectCard,
+                PlayerAction::SelectDiscardableStack
+            ]),
+            Some("actions to add")
+        );
+        assert_eq!(
+            auto.action_requires_rule(&[PlayerAction::SelectPlayableStack]),
+            Some("actions to add")
+        );
+        assert_eq!(
+            auto.action_requires_rule(&[
+                PlayerAction::SelectCard,
+                PlayerAction::SelectPlayableStack
+            ]),
+            None
+        );
+        assert_eq!(
+            auto.action_requires_rule(&[PlayerAction::SelectDiscardableStack]),
+            None
+        );
+    }
+
+    #[test]
+    fn jump_in() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new("p2".to_owned(), vec![]),
+                Player::new("p3".to_owned(), vec![]),
+            ],
+            MaoCore::init_stacks().unwrap(),
+        );
+        let top = mao.top_playable_cards()[0].1.unwrap().to_owned();
+        let other = Card::new(
+            top.get_value().to_owned(),
+            CardType::Common(match top.get_sign() {
+                CardType::Common(CommonCardType::Heart) => CommonCardType::Spade,
+                _ => CommonCardType::Heart,
+            }),
+            None,
+        );
+        mao.players_mut()[2]
+            .get_cards_mut()
+            .extend([other, top.to_owned()]);
+        assert!(matches!(
+            mao.jump_in(2, 1),
+            Err(Error::DisabledByConfig { .. })
+        ));
+
+        let mut config = mao.config().to_owned();
+        config.allow_jump_in = true;
+        mao.set_config(config);
+
+        // same value but not the same sign
+        assert_eq!(mao.jump_in(2, 0).unwrap().len(), 1);
+        assert_eq!(mao.players()[2].len(), 3);
+        assert_eq!(mao.player_turn(), 1);
+
+        assert!(mao.jump_in(2, 1).unwrap().is_empty());
+        assert_eq!(mao.players()[2].len(), 2);
+        assert_eq!(mao.stacks()[1].top(), Some(&top));
+        // p3 took the turn, now it is the turn of the one after it
+        assert_eq!(mao.player_turn(), 0);
+    }
+
+    #[test]
+    fn hand_summary() {
+        let mut hidden = card(5, CommonCardType::Spade);
+        hidden.set_owner_can_see_it(false);
+        let mut shown = card(9, CommonCardType::Diamond);
+        shown.set_other_can_see_it(true);
+        let mao = mao_from(
+            vec![
+                Player::new(
+                    "p1".to_owned(),
+                    vec![card(2, CommonCardType::Club), hidden, shown.to_owned()],
+                ),
+                Player::new("p2".to_owned(), vec![card(3, CommonCardType::Heart)]),
+            ],
+            MaoCore::init_stacks().unwrap(),
+        );
+
+        let summary = mao.hand_summary(Some(0));
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary[0].pseudo, "p1");
+        assert_eq!(summary[0].card_count, 3);
+        assert_eq!(
+            summary[0].visible_cards,
+            vec![
+                Some(card(2, CommonCardType::Club)),
+                None,
+                Some(shown.to_owned())
+            ]
+        );
+        assert_eq!(summary[1].card_count, 1);
+        assert_eq!(summary[1].visible_cards, vec![None]);
+
+        let summary = mao.hand_summary(None);
+        assert_eq!(summary[0].visible_cards, vec![None, None, Some(shown)]);
+    }
+
+    #[test]
+    fn wrong_turn_penality_count() {
+        let mut mao = mao_from_config(
+            "wrong_turn_penality_count",
+            Config {
+                wrong_turn_penality: 3,
+                ..Default::default()
+            },
+        );
+        mao.players_mut().extend([
+            Player::new("p1".to_owned(), vec![card(3, CommonCardType::Diamond)]),
+            Player::new("p2".to_owned(), vec![]),
+        ]);
+        mao.on_action(MaoInteraction::new(
+            Some(IdString::Index(0)),
+            PlayerAction::SelectCard,
+        ));
+        let MaoInteractionResult::Leaf { interactions, func } = mao.on_action(MaoInteraction::new(
+            Some(IdString::Index(1)),
+            PlayerAction::SelectPlayableStack,
+        )) else {
+            panic!("playing should be a leaf");
+        };
+        let res = func(0, &mut mao, &interactions).unwrap();
+        assert!(matches!(
+            res.as_slice(),
+            [WrongPlayerInteraction::Disallow(disallow)] if disallow.msg == "It is not your turn"
+        ));
+        assert_eq!(mao.players()[0].len(), 4);
+    }
+
+    #[test]
+    fn players_in_turn_order() {
+        let mut mao = mao_from(
+            (1..=4)
+                .map(|i| Player::new(format!("p{}", i), vec![]))
+                .collect(),
+            MaoCore::init_stacks().unwrap(),
+        );
+        assert_eq!(mao.players_in_turn_order(), vec![1, 2, 3, 0]);
+        mao.update_turn(PlayerTurnChange::Rotate(PlayerTurnUpdater::Set(2)));
+        assert_eq!(mao.players_in_turn_order(), vec![2, 1, 0, 3]);
+        assert!(mao_from(vec![], MaoCore::init_stacks().unwrap())
+            .players_in_turn_order()
+            .is_empty());
+    }
+
+    #[test]
+    fn top_of_discard() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new(
+                    "p2".to_owned(),
+                    vec![
+                        card(4, CommonCardType::Club),
+                        card(7, CommonCardType::Heart),
+                    ],
+                ),
+            ],
+            MaoCore::init_stacks().unwrap(),
+        );
+        assert_eq!(mao.top_of_discard(), None);
+        mao.discard_from_hand(1, 1, None).unwrap();
+        assert_eq!(mao.top_of_discard(), Some(&card(7, CommonCardType::Heart)));
+        mao.discard_from_hand(1, 0, None).unwrap();
+        assert_eq!(mao.top_of_discard(), Some(&card(4, CommonCardType::Club)));
+        assert_eq!(mao_from(vec![], vec![]).top_of_discard(), None);
+    }
+
+    #[test]
+    fn executed_interactions() {
+        let mut mao = mao_from_config("executed_interactions", Config::default());
+        mao.players_mut().extend([
+            Player::new("p1".to_owned(), vec![]),
+            Player::new("p2".to_owned(), vec![card(3, CommonCardType::Diamond)]),
+        ]);
+        assert!(mao.executed_interactions().is_empty());
+
+        let select_card = MaoInteraction::new(Some(IdString::Index(0)), PlayerAction::SelectCard);
+        let select_stack =
+            MaoInteraction::new(Some(IdString::Index(1)), PlayerAction::SelectPlayableStack);
+        mao.on_action(select_card.to_owned());
+        assert_eq!(mao.executed_interactions(), vec![select_card.to_owned()]);
+
+        assert!(matches!(
+            mao.on_action(select_stack.to_owned()),
+            MaoInteractionResult::Leaf { .. }
+        ));
+        // the action is completed but still available
+        assert!(mao.get_executed_actions().is_empty());
+        assert_eq!(mao.executed_interactions(), vec![select_card, select_stack]);
+    }
+
+    #[test]
+    fn draw_until_playable() {
+        let stacks = vec![
+            Stack::new(
+                vec![
+                    card(9, CommonCardType::Spade),
+                    card(3, CommonCardType::Heart),
+                ],
+                false,
+                vec![StackType::Drawable],
+            ),
+            Stack::new(
+                vec![card(5, CommonCardType::Club)],
+                true,
+                vec![StackType::Playable],
+            ),
+            Stack::new(vec![], true, vec![StackType::Discardable]),
+        ];
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![card(2, CommonCardType::Heart)]),
+                Player::new("p2".to_owned(), vec![]),
+            ],
+            stacks,
+        );
+        assert!(mao.legal_moves(0).is_empty());
+        assert_eq!(mao.draw_until_playable(0).unwrap(), 2);
+        assert_eq!(mao.legal_moves(0), vec![(2, 1)]);
+        assert_eq!(mao.draw_until_playable(0).unwrap(), 0);
+
+        // no card can ever be placed
+        let stacks = vec![
+            Stack::new(
+                vec![card(3, CommonCardType::Heart)],
+                false,
+                vec![StackType::Drawable],
+            ),
+            Stack::new(
+                vec![
+                    card(4, CommonCardType::Diamond),
+                    card(5, CommonCardType::Club),
+                ],
+                true,
+                vec![StackType::Playable],
+            ),
+        ];
+        let mut mao = mao_from(
+            vec![Player::new(
+                "p1".to_owned(),
+                vec![card(2, CommonCardType::Heart)],
+            )],
+            stacks,
+        );
+        // the covered card of the playable stack is drawn after a refill
+        assert_eq!(mao.draw_until_playable(0).unwrap(), 2);
+        assert!(mao.legal_moves(0).is_empty());
+    }
+
+    #[test]
+    fn shuffle_player_hand() {
+        let hand: Vec<Card> = (1..=13).map(|v| card(v, CommonCardType::Heart)).collect();
+        let shuffled = |seed| {
+            let mut mao = mao_from(
+                vec![Player::new("p1".to_owned(), hand.to_owned())],
+                MaoCore::init_stacks().unwrap(),
+            );
+            mao.set_seed(seed);
+            mao.shuffle_player_hand(0).unwrap();
+            assert!(mao.shuffle_player_hand(1).is_err());
+            mao.players()[0].get_cards().to_vec()
+        };
+        let first = shuffled(42);
+        assert_eq!(first, shuffled(42));
+        assert_ne!(first, hand);
+
+        let mut sorted = first;
+        sorted.sort();
+        assert_eq!(sorted, hand);
+    }
+
+    #[test]
+    fn rotate_hands() {
+        let hands = [
+            vec![card(1, CommonCardType::Heart)],
+            vec![
+                card(2, CommonCardType::Heart),
+                card(3, CommonCardType::Heart),
+            ],
+            vec![],
+        ];
+        let mut mao = mao_from(
+            hands
+                .iter()
+                .enumerate()
+                .map(|(i, hand)| Player::new(format!("p{}", i), hand.to_owned()))
+                .collect(),
+            MaoCore::init_stacks().unwrap(),
+        );
+        mao.rotate_hands(1);
+        assert_eq!(mao.players()[0].get_cards(), hands[2].as_slice());
+        assert_eq!(mao.players()[1].get_cards(), hands[0].as_slice());
+        assert_eq!(mao.players()[2].get_cards(), hands[1].as_slice());
+
+        mao.rotate_hands(-4);
+        assert_eq!(mao.players()[0].get_cards(), hands[0].as_slice());
+        assert_eq!(mao.players().iter().map(|p| p.len()).sum::<usize>(), 3);
+    }
+
+    #[test]
+    fn set_stack_visible() {
+        let mut mao = mao_from(vec![], MaoCore::init_stacks().unwrap());
+        assert!(!mao.stacks()[0].visible());
+        mao.set_stack_visible(0, true).unwrap();
+        assert!(mao.stacks()[0].visible());
+        mao.set_stack_visible(0, false).unwrap();
+        assert!(!mao.stacks()[0].visible());
+        assert!(matches!(
+            mao.set_stack_visible(3, true),
+            Err(Error::InvalidStackIndex {
+                stack_index: 3,
+                len: 3
+            })
+        ));
+        // the event is not recordable
+        assert!(mao.players_events().is_empty());
+        assert!(!MaoEvent::StackVisibilityChanged {
+            stack_index: 0,
+            visible: true
+        }
+        .is_recordable());
+    }
+
+    #[test]
+    fn validate_automaton_paths() {
+        let mut paths = generate_path();
+        assert!(Automaton::validate_paths(&paths).is_ok());
+        assert!(Automaton::from_iter(paths.to_owned()).validate().is_ok());
+        paths.extend(actions_to_add());
+        assert!(Automaton::validate_paths(&paths).is_ok());
+
+        let mut duplicated = paths.to_owned();
+        duplicated.push(generate_path().remove(0));
+        assert!(matches!(
+            Automaton::validate_paths(&duplicated),
+            Err(Error::InvalidAutomaton { .. })
+        ));
+
+        let mut not_executable = generate_path();
+        not_executable[1][0].func = None;
+        assert!(Automaton::validate_paths(&not_executable).is_err());
+        assert!(Automaton::validate_paths(&[Vec::<NodeState>::new()]).is_err());
+    }
+
+    #[test]
+    fn find_card_holder() {
+        let mut hidden = card(12, CommonCardType::Spade);
+        hidden.set_other_can_see_it(true);
+        let mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![card(2, CommonCardType::Club)]),
+                Player::new(
+                    "p2".to_owned(),
+                    vec![card(5, CommonCardType::Heart), hidden],
+                ),
+            ],
+            MaoCore::init_stacks().unwrap(),
+        );
+        assert_eq!(
+            mao.find_card_holder(&card(12, CommonCardType::Spade)),
+            Some((1, 1))
+        );
+        assert_eq!(
+            mao.find_card_holder(&card(2, CommonCardType::Club)),
+            Some((0, 0))
+        );
+        assert_eq!(mao.find_card_holder(&card(2, CommonCardType::Heart)), None);
+    }
+
+    #[test]
+    fn refill_threshold() {
+        let mut mao = mao_from_config(
+            "refill_threshold",
+            Config {
+                refill_threshold: Some(2),
+                ..Default::default()
+            },
+        );
+        *mao.stacks_mut() = vec![
+            Stack::new(
+                vec![card(1, CommonCardType::Club), card(2, CommonCardType::Club)],
+                false,
+                vec![StackType::Drawable],
+            ),
+            Stack::new(
+                vec![card(3, CommonCardType::Club), card(4, CommonCardType::Club)],
+                true,
+                vec![StackType::Playable],
+            ),
+            Stack::new(
+                vec![card(5, CommonCardType::Club)],
+                true,
+                vec![StackType::Discardable],
+            ),
+        ];
+        mao.players_mut().extend([
+            Player::new("p1".to_owned(), vec![]),
+            Player::new("p2".to_owned(), vec![]),
+        ]);
+        let MaoInteractionResult::Leaf { interactions, func } =
+            mao.on_action(MaoInteraction::new(None, PlayerAction::SelectDrawableStack))
+        else {
+            panic!("drawing should be a leaf");
+        };
+        func(1, &mut mao, &interactions).unwrap();
+
+        assert_eq!(
+            mao.players()[1].get_cards(),
+            &[card(2, CommonCardType::Club)]
+        );
+        // only one card was left, the stack has been refilled
+        assert_eq!(mao.stacks()[0].len(), 3);
+        assert_eq!(
+            mao.stacks()[1].get_cards(),
+            &[card(4, CommonCardType::Club)]
+        );
+        assert!(mao.stacks()[2].is_empty());
+    }
+
+    #[test]
+    fn play_card() {
+        let stacks = vec![
+            Stack::new(vec![], false, vec![StackType::Drawable]),
+            Stack::new(
+                vec![card(5, CommonCardType::Club)],
+                true,
+                vec![StackType::Playable],
+            ),
+        ];
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new(
+                    "p2".to_owned(),
+                    vec![
+                        card(2, CommonCardType::Heart),
+                        card(5, CommonCardType::Diamond),
+                    ],
+                ),
+            ],
+            stacks,
+        );
+        assert!(mao.play_card(1, 2, Some(1)).is_err());
+        assert!(mao.play_card(1, 0, Some(2)).is_err());
+
+        assert!(mao.play_card(1, 1, Some(1)).unwrap().is_empty());
+        assert_eq!(
+            mao.players()[1].get_cards(),
+            &[card(2, CommonCardType::Heart)]
+        );
+        assert_eq!(
+            mao.stacks()[1].top(),
+            Some(&card(5, CommonCardType::Diamond))
+        );
+        assert_eq!(mao.player_turn(), 0);
+    }
+
+    #[test]
+    fn public_state() {
+        let mut hidden = card(5, CommonCardType::Spade);
+        hidden.set_owner_can_see_it(false);
+        let mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![card(2, CommonCardType::Club)]),
+                Player::new(
+                    "p2".to_owned(),
+                    vec![card(3, CommonCardType::Heart), hidden],
+                ),
+            ],
+            MaoCore::init_stacks().unwrap(),
+        );
+        let state = mao.public_state(Some(1));
+        assert_eq!(state.hands[0].visible_cards, vec![None]);
+        assert_eq!(
+            state.hands[1].visible_cards,
+            vec![Some(card(3, CommonCardType::Heart)), None]
+        );
+        // the drawable stack is face down
+        assert_eq!(state.stacks[0].top, None);
+        assert_eq!(state.stacks[0].card_count, mao.stacks()[0].len());
+        assert_eq!(state.stacks[1].top.as_ref(), mao.stacks()[1].top());
+        assert_eq!(state.player_turn, 1);
+        assert_eq!(state.turn, 1);
+        assert!(state.activated_rules.is_empty());
+
+        let json = serde_json::to_value(&state).unwrap();
+        assert!(json["hands"][0]["visible_cards"][0].is_null());
+        assert!(json["hands"][1]["visible_cards"][1].is_null());
+    }
+
+    #[test]
+    fn preview_card_effects() {
+        let mut mao = mao_from(
+            (1..=3)
+                .map(|i| Player::new(format!("p{}", i), vec![]))
+                .collect(),
+            MaoCore::init_stacks().unwrap(),
+        );
+        mao.set_config(Config {
+            cards_effects: HashMap::from([(
+                CardEffectsKey::new(None, Some(CardValue::Number(8))),
+                SingOrMult::Multiple(vec![
+                    SingleCardEffect::PlayerTurnChange(PlayerTurnChange::Rotate(
+                        PlayerTurnUpdater::Update(1),
+                    )),
+                    SingleCardEffect::CardPlayerAction(CardPlayerAction::Say(vec![
+                        SingOrMult::Single("eight".to_owned()),
+                    ])),
+                ]),
+            )]),
+            ..Default::default()
+        });
+
+        let preview = mao.preview_card_effects(&card(8, CommonCardType::Diamond));
+        assert_eq!(preview.turn_changes.len(), 1);
+        assert_eq!(preview.player_actions.len(), 1);
+        assert!(preview.reverses_direction);
+        assert_eq!(preview.next_player, 0);
+
+        let preview = mao.preview_card_effects(&card(7, CommonCardType::Diamond));
+        assert!(preview.turn_changes.is_empty());
+        assert!(!preview.reverses_direction);
+        assert_eq!(preview.next_player, 2);
+        assert_eq!(mao.player_turn(), 1);
+    }
+
+    #[test]
+    fn pass_turn() {
+        let mut mao = mao_from(
+            (1..=3)
+                .map(|i| Player::new(format!("p{}", i), vec![]))
+                .collect(),
+            MaoCore::init_stacks().unwrap(),
+        );
+        assert!(matches!(
+            mao.pass_turn(1),
+            Err(Error::DisabledByConfig { .. })
+        ));
+        mao.set_config(Config {
+            allow_pass: true,
+            ..Default::default()
+        });
+        assert!(mao.pass_turn(0).is_err());
+        assert_eq!(mao.player_turn(), 1);
+
+        mao.pass_turn(1).unwrap();
+        assert_eq!(mao.player_turn(), 2);
+        mao.pass_turn(2).unwrap();
+        assert_eq!(mao.player_turn(), 0);
+    }
+
+    #[test]
+    fn mao_event_actor() {
+        let card_event = CardEvent {
+            played_card: card(3, CommonCardType::Heart),
+            card_index: 0,
+            player_index: 2,
+            stack_index: Some(1),
+        };
+        let with_actor = [
+            MaoEvent::PlayedCardEvent(card_event.clone()),
+            MaoEvent::DiscardCardEvent(card_event.clone()),
+            MaoEvent::DrawedCardEvent(card_event),
+            MaoEvent::GiveCardEvent {
+                card: card(1, CommonCardType::Spade),
+                from_player_index: 2,
+                target: StackTarget::Player(0),
+            },
+            MaoEvent::SayEvent {
+                message: String::from("hello"),
+                player_index: 2,
+            },
+            MaoEvent::PhysicalEvent {
+                physical_name: String::from("knock"),
+                player_index: 2,
+            },
+            MaoEvent::TurnPassed { player_index: 2 },
+        ];
+        for event in with_actor {
+            assert_eq!(event.actor(), Some(2), "{:?}", event);
+        }
+        let without_actor = [
+            MaoEvent::StackPropertyRunsOut {
+                empty_stack_index: StackTarget::Stack(0),
+            },
+            MaoEvent::StackRefilled {
+                stack_index: 0,
+                cards_added: 2,
+            },
+            MaoEvent::StackVisibilityChanged {
+                stack_index: 0,
+                visible: true,
+            },
+            MaoEvent::HandLimitExceeded { player_index: 2 },
+            MaoEvent::GameStart,
+            MaoEvent::PlayerJoined { player_index: 2 },
+            MaoEvent::RuleActivated {
+                rule_name: String::from("rule"),
+            },
+            MaoEvent::RuleDeactivated {
+                rule_name: String::from("rule"),
+            },
+            MaoEvent::EndPlayerTurn { events: vec![] },
+            MaoEvent::PlayerPenality { player_target: 2 },
+            MaoEvent::PenaltyApplied {
+                player_index: 2,
+                cards_drawn: 1,
+            },
+            MaoEvent::VerifyEvent,
+        ];
+        for event in without_actor {
+            assert_eq!(event.actor(), None, "{:?}", event);
+        }
+    }
+
+    #[test]
+    fn rule_info_invalid_index() {
+        let mao = mao_from(vec![], MaoCore::init_stacks().unwrap());
+        assert!(matches!(
+            mao.rule_info(0),
+            Err(Error::InvalidRuleIndex {
+                rule_index: 0,
+                len: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn abort_current_interaction() {
+        let mut mao = mao_from_config("abort_current_interaction", Config::default());
+        assert!(mao.abort_current_interaction().is_empty());
+
+        let select_card = MaoInteraction::new(Some(IdString::Index(0)), PlayerAction::SelectCard);
+        assert!(matches!(
+            mao.on_action(select_card.to_owned()),
+            MaoInteractionResult::AdvancedNextState
+        ));
+        assert!(mao.automaton().current_state().is_some());
+
+        assert_eq!(mao.abort_current_interaction(), vec![select_card]);
+        assert!(mao.automaton().current_state().is_none());
+        assert!(mao.executed_interactions().is_empty());
+    }
+
+    #[test]
+    fn jocker_undefined_color_rendering() {
+        let jocker = |color: CardColor| CardType::Jocker {
+            desc: String::new(),
+            color,
+        };
+        let blue = jocker(CardColor::Undefined(String::from("blue"))).to_card_string();
+        let red = jocker(CardColor::Red).to_card_string();
+        let black = jocker(CardColor::Black).to_card_string();
+        assert_ne!(blue, red);
+        assert_ne!(blue, black);
+        assert!(blue.contains('J'));
+        // unknown names and disabled colors fall back to the plain letter
+        assert_eq!(
+            jocker(CardColor::Undefined(String::from("unknown"))).to_card_string(),
+            "J"
+        );
+        assert_eq!(
+            jocker(CardColor::Undefined(String::from("blue"))).to_card_string_colored(false),
+            "J"
+        );
+    }
+
+    #[test]
+    fn replace_card() {
+        let mut mao = mao_from(
+            vec![Player::new(
+                "p1".to_owned(),
+                vec![
+                    card(2, CommonCardType::Heart),
+                    card(5, CommonCardType::Club),
+                ],
+            )],
+            MaoCore::init_stacks().unwrap(),
+        );
+        let old = mao
+            .replace_card(StackTarget::Player(0), 1, card(9, CommonCardType::Spade))
+            .unwrap();
+        assert_eq!(old, card(5, CommonCardType::Club));
+        assert_eq!(
+            mao.players()[0].get_cards(),
+            &[
+                card(2, CommonCardType::Heart),
+                card(9, CommonCardType::Spade)
+            ]
+        );
+        assert!(matches!(
+            mao.replace_card(StackTarget::Player(0), 2, card(1, CommonCardType::Spade)),
+            Err(Error::InvalidCardIndex {
+                card_index: 2,
+                len: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn automaton_set_current_path() {
+        let mut mao = mao_from_config("automaton_set_current_path", Config::default());
+        let automaton = mao.automaton_mut();
+        automaton
+            .set_current_path(&[PlayerAction::SelectCard])
+            .unwrap();
+        assert_eq!(
+            automaton.current_state().map(|node| &node.action.action),
+            Some(&PlayerAction::SelectCard)
+        );
+
+        // a leaf cannot be restored and the state is kept
+        assert!(automaton
+            .set_current_path(&[PlayerAction::SelectCard, PlayerAction::SelectPlayableStack])
+            .is_err());
+        assert_eq!(
+            automaton.current_state().map(|node| &node.action.action),
+            Some(&PlayerAction::SelectCard)
+        );
+
+        automaton.set_current_path(&[]).unwrap();
+        assert!(automaton.current_state().is_none());
+    }
+
+    #[test]
+    fn start_game_deals_initial_hand_size() {
+        let mut mao = mao_from(
+            (1..=3)
+                .map(|i| Player::new(format!("p{}", i), vec![]))
+                .collect(),
+            vec![],
+        );
+        mao.start_game().unwrap();
+        assert!(mao.players().iter().all(|p| p.get_cards().len() == 7));
+
+        mao.set_config(Config {
+            initial_hand_size: 4,
+            ..Default::default()
+        });
+        mao.start_game().unwrap();
+        assert!(mao.players().iter().all(|p| p.get_cards().len() == 4));
+    }
+
+    #[test]
+    fn card_event_validate() {
+        let mao = mao_from(
+            vec![Player::new(
+                "p1".to_owned(),
+                vec![card(2, CommonCardType::Heart)],
+            )],
+            MaoCore::init_stacks().unwrap(),
+        );
+        let event = |player_index, card_index, stack_index| {
+            CardEvent::new(
+                card(2, CommonCardType::Heart),
+                player_index,
+                stack_index,
+                card_index,
+            )
+        };
+        assert!(event(0, 0, Some(1)).validate(&mao).is_ok());
+        assert!(event(0, 0, None).validate(&mao).is_ok());
+        assert!(matches!(
+            event(0, 4, Some(1)).validate(&mao),
+            Err(Error::InvalidCardIndex {
+                card_index: 4,
+                len: 1
+            })
+        ));
+        assert!(matches!(
+            event(1, 0, Some(1)).validate(&mao),
+            Err(Error::InvalidPlayerIndex { .. })
+        ));
+        assert!(matches!(
+            event(0, 0, Some(3)).validate(&mao),
+            Err(Error::InvalidStackIndex { .. })
+        ));
+    }
+
+    #[test]
+    fn play_card_not_held() {
+        let mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new("p2".to_owned(), vec![card(2, CommonCardType::Heart)]),
+            ],
+            MaoCore::init_stacks().unwrap(),
+        );
+        let mut hidden = card(2, CommonCardType::Heart);
+        hidden.set_owner_can_see_it(false);
+        assert!(CardEvent::new(hidden, 1, Some(1), 0).validate(&mao).is_ok());
+
+        let event = CardEvent::new(card(13, CommonCardType::Spade), 1, Some(1), 0);
+        assert!(matches!(
+            event.validate(&mao),
+            Err(Error::CardMismatch { card_index: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn required_physical_actions() {
+        let mut mao = mao_from(vec![], MaoCore::init_stacks().unwrap());
+        mao.set_config(Config {
+            cards_effects: HashMap::from([(
+                CardEffectsKey::new(
+                    Some(CardType::Common(CommonCardType::Club)),
+                    Some(CardValue::Number(5)),
+                ),
+                SingOrMult::Multiple(vec![
+                    SingleCardEffect::CardPlayerAction(CardPlayerAction::Physical(
+                        "punch".to_owned(),
+                    )),
+                    SingleCardEffect::CardPlayerAction(CardPlayerAction::Say(vec![
+                        SingOrMult::Single("five".to_owned()),
+                    ])),
+                ]),
+            )]),
+            ..Default::default()
+        });
+        assert_eq!(
+            mao.required_physical_actions(&card(5, CommonCardType::Club)),
+            vec!["punch".to_owned()]
+        );
+        assert!(mao
+            .required_physical_actions(&card(5, CommonCardType::Heart))
+            .is_empty());
+        assert!(mao
+            .required_physical_actions(&card(6, CommonCardType::Club))
+            .is_empty());
+    }
+
+    #[test]
+    fn stack_serde_round_trip() {
+        let stack = Stack::new(
+            vec![
+                card(4, CommonCardType::Spade),
+                card(12, CommonCardType::Heart),
+            ],
+            false,
+            vec![StackType::Drawable],
+        );
+        let json = serde_json::to_string(&stack).unwrap();
+        let restored: Stack = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, stack);
+
+        // the names displayed by StackType can be read back
+        let stack_type: StackType =
+            serde_json::from_str(&format!("\"{}\"", StackType::Drawable)).unwrap();
+        assert_eq!(stack_type, StackType::Drawable);
+    }
+
+    #[test]
+    fn penality_target_stack() {
+        let mut mao = mao_from(
+            vec![Player::new("p1".to_owned(), vec![])],
+            MaoCore::init_stacks().unwrap(),
+        );
+        let discard_len = mao.stacks()[2].len();
+        mao.common_penality_to_player(0).unwrap();
+        assert_eq!(mao.players()[0].len(), 1);
+
+        mao.set_config(Config {
+            penality_target: PenaltyTarget::Stack(2),
+            ..Default::default()
+        });
+        mao.common_penality_to_player(0).unwrap();
+        assert_eq!(mao.players()[0].len(), 1);
+        assert_eq!(mao.stacks()[2].len(), discard_len + 1);
+
+        mao.set_config(Config {
+            penality_target: PenaltyTarget::Stack(5),
+            ..Default::default()
+        });
+        assert!(matches!(
+            mao.common_penality_to_player(0),
+            Err(Error::InvalidStackIndex { .. })
+        ));
+    }
+
+    #[test]
+    fn rule_fire_counts_without_rules() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new("p2".to_owned(), vec![card(1, CommonCardType::Heart)]),
+            ],
+            MaoCore::init_stacks().unwrap(),
+        );
+        mao.play_card(1, 0, Some(1)).unwrap();
+        assert!(mao.rule_fire_counts().is_empty());
+        mao.init_new_game(1).unwrap();
+        assert!(mao.rule_fire_counts().is_empty());
+    }
+
+    #[test]
+    fn rule_fire_counts() {
+        let mut mao = MaoCore::new(
+            vec![Rule::from_fn("no_three", refuse_threes)],
+            MaoCore::init_stacks().unwrap(),
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new("p2".to_owned(), vec![card(3, CommonCardType::Heart)]),
+            ],
+            Automaton::from_iter(Vec::<Vec<NodeState>>::new()),
+        );
+        mao.activate_rule("no_three").unwrap();
+        let wrong_int = mao.play_card(1, 0, Some(1)).unwrap();
+        assert!(matches!(wrong_int[0], WrongPlayerInteraction::Disallow(_)));
+        // the ignored events are not counted
+        assert!(mao.on_say_action(1, "hello".to_owned()).unwrap().is_empty());
+        assert_eq!(mao.rule_fire_counts(), HashMap::from([("no_three", 1)]));
+
+        mao.init_new_game(1).unwrap();
+        assert!(mao.rule_fire_counts().is_empty());
+    }
+
+    #[test]
+    fn config_merge() {
+        let physical = |name: &str| {
+            SingleCardEffect::CardPlayerAction(CardPlayerAction::Physical(name.to_owned()))
+        };
+        let key = |value| CardEffectsKey::new(None, Some(CardValue::Number(value)));
+        let mut base = Config {
+            dirname: "base".to_owned(),
+            cards_effects: HashMap::from([
+                (key(1), SingOrMult::Single(physical("knock"))),
+                (key(2), SingOrMult::Single(physical("clap"))),
+            ]),
+            max_hand_size: Some(10),
+            ..Default::default()
+        };
+        base.merge(Config {
+            cards_effects: HashMap::from([
+                (key(1), SingOrMult::Single(physical("punch"))),
+                (key(3), SingOrMult::Single(physical("jump"))),
+            ]),
+            refill_threshold: Some(2),
+            ..Default::default()
+        });
+
+        assert_eq!(base.dirname, "base");
+        assert_eq!(base.max_hand_size, Some(10));
+        assert_eq!(base.refill_threshold, Some(2));
+        assert_eq!(base.cards_effects.len(), 3);
+        let mut mao = mao_from(vec![], vec![]);
+        mao.set_config(base.clone());
+        let names = |value| mao.required_physical_actions(&card(value, CommonCardType::Spade));
+        assert_eq!(names(1), vec!["knock".to_owned(), "punch".to_owned()]);
+        assert_eq!(names(2), vec!["clap".to_owned()]);
+        assert_eq!(names(3), vec!["jump".to_owned()]);
+
+        base.merge(Config {
+            dirname: "variant".to_owned(),
+            ..Default::default()
+        });
+        assert_eq!(base.dirname, "variant");
+    }
+
+    #[test]
+    fn merged_card_effects_order() {
+        let key = CardEffectsKey::new(None, Some(CardValue::Number(4)));
+        let skip = SingleCardEffect::PlayerTurnChange(PlayerTurnChange::Update(
+            PlayerTurnUpdater::Update(1),
+        ));
+        let reverse = SingleCardEffect::PlayerTurnChange(PlayerTurnChange::Rotate(
+            PlayerTurnUpdater::Update(1),
+        ));
+        let mut config = Config {
+            cards_effects: HashMap::from([(key.to_owned(), SingOrMult::Single(skip))]),
+            ..Default::default()
+        };
+        config.merge(Config {
+            cards_effects: HashMap::from([(key.to_owned(), SingOrMult::Single(reverse))]),
+            ..Default::default()
+        });
+        let effects = config.cards_effects.get(&key).unwrap();
+        assert!(matches!(
+            effects.get(0),
+            Some(SingleCardEffect::PlayerTurnChange(
+                PlayerTurnChange::Update(PlayerTurnUpdater::Update(1))
+            ))
+        ));
+        assert!(matches!(
+            effects.get(1),
+            Some(SingleCardEffect::PlayerTurnChange(
+                PlayerTurnChange::Rotate(_)
+            ))
+        ));
+        assert!(effects.get(2).is_none());
+
+        let mut mao = mao_from(
+            (1..=3)
+                .map(|i| Player::new(format!("p{}", i), vec![]))
+                .collect(),
+            vec![],
+        );
+        mao.set_config(config);
+        let preview = mao.preview_card_effects(&card(4, CommonCardType::Club));
+        assert!(matches!(
+            preview.turn_changes.as_slice(),
+            [
+                PlayerTurnChange::Update(PlayerTurnUpdater::Update(1)),
+                PlayerTurnChange::Rotate(_)
+            ]
+        ));
+    }
+
+    #[test]
+    fn give_penality_to_another_player() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new("p2".to_owned(), vec![card(1, CommonCardType::Heart)]),
+            ],
+            MaoCore::init_stacks().unwrap(),
+        );
+        let event = MaoEvent::GiveCardEvent {
+            card: card(1, CommonCardType::Heart),
+            from_player_index: 1,
+            target: StackTarget::Player(0),
+        };
+        let result = MaoEventResult::new(
+            Necessary::BasicRule(false),
+            MaoEventResultType::GivePenality(GivePenality {
+                rule: "test".to_owned(),
+                msg: "p1 did not thank p2".to_owned(),
+                player_index: 0,
+                penality: None,
+            }),
+        );
+        let wrong_int = mao
+            .propagate_on_event_results_and_execute(1, &event, [&result])
+            .unwrap();
+        let [WrongPlayerInteraction::GivePenality(give)] = wrong_int.as_slice() else {
+            panic!("expected a single GivePenality, got {:?}", wrong_int);
+        };
+        assert_eq!(give.to_string(), "test: p1 did not thank p2 (player 0)");
+
+        mao.give_penality(give).unwrap();
+        assert_eq!(mao.players()[0].len(), 1);
+        assert_eq!(mao.players()[1].len(), 1);
+    }
+
+    #[test]
+    fn dispatch_event() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new("p2".to_owned(), vec![]),
+            ],
+            MaoCore::init_stacks().unwrap(),
+        );
+        assert!(mao.dispatch_event(MaoEvent::GameStart).unwrap().is_empty());
+        assert!(mao.players().iter().all(|p| p.is_empty()));
+
+        let say = MaoEvent::SayEvent {
+            message: "hello".to_owned(),
+            player_index: 0,
+        };
+        assert!(mao.dispatch_event(say.to_owned()).unwrap().is_empty());
+        assert_eq!(mao.events_for_player(0), vec![&say]);
+        assert_eq!(mao.player_turn(), 1);
+    }
+
+    #[test]
+    fn dispatch_event_penalizes() {
+        let mut mao = MaoCore::new(
+            vec![Rule::from_fn("still", refuse_physicals)],
+            MaoCore::init_stacks().unwrap(),
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new("p2".to_owned(), vec![]),
+            ],
+            Automaton::from_iter(Vec::<Vec<NodeState>>::new()),
+        );
+        mao.activate_rule("still").unwrap();
+        assert!(mao.dispatch_event(MaoEvent::GameStart).unwrap().is_empty());
+
+        let wrong_int = mao
+            .dispatch_event(MaoEvent::PhysicalEvent {
+                physical_name: "knock".to_owned(),
+                player_index: 0,
+            })
+            .unwrap();
+        assert_eq!(wrong_int.len(), 1);
+        // the actor of the event is penalized, not the current player
+        assert_eq!(mao.players()[0].len(), 1);
+        assert!(mao.players()[1].is_empty());
+        assert_eq!(mao.rule_fire_counts(), HashMap::from([("still", 1)]));
+    }
+
+    #[test]
+    fn initial_direction() {
+        let mut mao = mao_from_config(
+            "initial_direction",
+            Config {
+                initial_direction: -1,
+                ..Default::default()
+            },
+        );
+        mao.players_mut()
+            .extend((1..=3).map(|i| Player::new(format!("p{}", i), vec![])));
+        assert_eq!(mao.peek_next_player(&PlayerTurnChange::default()), 0);
+        mao.set_player_turn(0).unwrap();
+        assert_eq!(mao.peek_next_player(&PlayerTurnChange::default()), 2);
+
+        mao.set_config(Config::default());
+        mao.init_new_game(1).unwrap();
+        assert_eq!(mao.player_turn(), 1);
+        assert_eq!(mao.peek_next_player(&PlayerTurnChange::default()), 2);
+
+        let mut config = Config {
+            dirname: std::env::temp_dir().to_string_lossy().into_owned(),
+            initial_direction: 2,
+            ..Default::default()
+        };
+        assert!(matches!(config.verify(), Err(Error::InvalidConfig { .. })));
+    }
+
+    #[test]
+    fn prune_unreachable_after_removal() {
+        let mut actions = generate_path();
+        actions.extend(actions_to_add());
+        let mut automaton = Automaton::from_iter(actions);
+        assert_eq!(automaton.prune_unreachable(), 0);
+
+        // the prefix SelectCard is shared with a path of actions_to_add
+        assert_eq!(automaton.remove_paths(vec![generate_path().remove(0)]), 1);
+        assert!(automaton.validate().is_ok());
+        assert!(automaton.path_exists_full(&[
+            PlayerAction::SelectCard,
+            PlayerAction::SelectCard,
+            PlayerAction::SelectDiscardableStack
+        ]));
+        assert!(!automaton
+            .path_exists_full(&[PlayerAction::SelectCard, PlayerAction::SelectPlayableStack]));
+
+        assert_eq!(automaton.remove_paths(vec![actions_to_add().remove(0)]), 3);
+        assert_eq!(automaton.prune_unreachable(), 0);
+        assert!(automaton.validate().is_ok());
+        let mut remaining = generate_path();
+        remaining.remove(0);
+        remaining.push(actions_to_add().remove(1));
+        assert!(automaton.structurally_eq(&Automaton::from_iter(remaining)));
+    }
+
+    #[test]
+    fn drawable_composition() {
+        let stacks = vec![
+            Stack::new(
+                vec![
+                    card(3, CommonCardType::Heart),
+                    card(3, CommonCardType::Spade),
+                    card(7, CommonCardType::Heart),
+                ],
+                false,
+                vec![StackType::Drawable],
+            ),
+            Stack::new(
+                vec![card(3, CommonCardType::Club)],
+                false,
+                vec![StackType::Drawable],
+            ),
+            // not drawable so not counted
+            Stack::new(
+                vec![card(7, CommonCardType::Club)],
+                true,
+                vec![StackType::Playable],
+            ),
+        ];
+        let mao = mao_from(vec![], stacks);
+        assert_eq!(
+            mao.drawable_composition(),
+            HashMap::from([(CardValue::Number(3), 3), (CardValue::Number(7), 1)])
+        );
+        assert_eq!(
+            mao.drawable_suits(),
+            HashMap::from([
+                (CardType::Common(CommonCardType::Heart), 2),
+                (CardType::Common(CommonCardType::Spade), 1),
+                (CardType::Common(CommonCardType::Club), 1),
+            ])
+        );
+    }
+
+    #[test]
+    fn last_played() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new("p2".to_owned(), vec![card(1, CommonCardType::Heart)]),
+            ],
+            vec![Stack::new(
+                vec![card(5, CommonCardType::Heart)],
+                true,
+                vec![StackType::Playable],
+            )],
+        );
+        assert_eq!(mao.last_played(), None);
+        assert!(mao.play_card(1, 0, Some(0)).unwrap().is_empty());
+        assert_eq!(
+            mao.last_played(),
+            Some((1, &card(1, CommonCardType::Heart)))
+        );
+    }
+
+    #[test]
+    fn init_stacks_from_empty_deck() {
+        assert!(matches!(
+            MaoCore::init_stacks_from(vec![]),
+            Err(Error::EmptyDeck)
+        ));
+        let stacks = MaoCore::init_stacks_from(vec![
+            card(2, CommonCardType::Spade),
+            card(6, CommonCardType::Club),
+        ])
+        .unwrap();
+        assert_eq!(stacks.len(), 3);
+        assert_eq!(stacks[0].len(), 1);
+        assert_eq!(stacks[1].len(), 1);
+        assert!(stacks[2].is_empty());
+    }
+
+    #[test]
+    fn node_state_path_builder() {
+        let path = NodeStatePathBuilder::new(&[
+            PlayerAction::SelectCard,
+            PlayerAction::SelectDiscardableStack,
+        ])
+        .rule("discard")
+        .build(|_, _, _| Ok(vec![]));
+        let manual = vec![
+            NodeState::new(
+                MaoInteraction::new(None, PlayerAction::SelectCard),
+                None,
+                None,
+            ),
+            NodeState::new(
+                MaoInteraction::new(None, PlayerAction::SelectDiscardableStack),
+                Some(|_, _, _| Ok(vec![])),
+                Some(String::from("discard")),
+            ),
+        ];
+        assert_eq!(path, manual);
+        assert!(path[0].func.is_none() && path[1].func.is_some());
+
+        let mut automaton = Automaton::from_iter(generate_path());
+        automaton.extend(vec![path]);
+        assert_eq!(automaton.paths_for_rule("discard"), vec![manual]);
+        assert!(NodeStatePathBuilder::new(&[])
+            .build(|_, _, _| Ok(vec![]))
+            .is_empty());
+    }
+
+    #[test]
+    fn can_refill() {
+        let mut mao = mao_from(
+            vec![],
+            vec![
+                Stack::new(vec![], false, vec![StackType::Drawable]),
+                Stack::new(
+                    vec![card(4, CommonCardType::Heart)],
+                    true,
+                    vec![StackType::Playable],
+                ),
+                Stack::new(
+                    vec![card(8, CommonCardType::Club)],
+                    true,
+                    vec![StackType::Discardable],
+                ),
+            ],
+        );
+        assert!(mao.can_refill());
+
+        // the top card of a playable stack is never recycled
+        mao.get_stack_target(StackTarget::Stack(2))
+            .unwrap()
+            .remove_card(0)
+            .unwrap();
+        assert!(!mao.can_refill());
+        mao.push_card_into_stack_target(StackTarget::Stack(1), card(5, CommonCardType::Heart))
+            .unwrap();
+        assert!(mao.can_refill());
+    }
+
+    #[test]
+    fn can_place_infinity_cards() {
+        let mut mao = mao_from(vec![], vec![]);
+        let stack = Stack::new(
+            vec![card(6, CommonCardType::Spade)],
+            true,
+            vec![StackType::Playable],
+        );
+        let plus = Card::new(
+            CardValue::PlusInfinity,
+            CardType::Common(CommonCardType::Heart),
+            None,
+        );
+        let minus = Card::new(
+            CardValue::MinusInfinity,
+            CardType::Common(CommonCardType::Spade),
+            None,
+        );
+        assert_eq!(
+            mao.can_place(&plus, Some(&stack)),
+            PlayerTurnResult::CanPlay
+        );
+        assert!(matches!(
+            mao.can_place(&minus, Some(&stack)),
+            PlayerTurnResult::Other { .. }
+        ));
+
+        mao.set_config(Config {
+            infinity_wild: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            mao.can_place(&minus, Some(&stack)),
+            PlayerTurnResult::CanPlay
+        );
+        assert_eq!(
+            mao.can_place(&plus, Some(&stack)),
+            PlayerTurnResult::CanPlay
+        );
+    }
+
+    #[test]
+    fn swap_hands() {
+        let hand_a = vec![
+            card(2, CommonCardType::Heart),
+            card(3, CommonCardType::Club),
+        ];
+        let hand_b = vec![card(10, CommonCardType::Spade)];
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), hand_a.to_owned()),
+                Player::new("p2".to_owned(), vec![]),
+                Player::new("p3".to_owned(), hand_b.to_owned()),
+            ],
+            vec![],
+        );
+        mao.swap_hands(0, 2).unwrap();
+        assert_eq!(mao.players()[0].get_cards(), hand_b.as_slice());
+        assert_eq!(mao.players()[2].get_cards(), hand_a.as_slice());
+        assert_eq!(mao.players().iter().map(|p| p.len()).sum::<usize>(), 3);
+        // swapping a hand with itself changes nothing
+        mao.swap_hands(0, 0).unwrap();
+        assert_eq!(mao.players()[0].get_cards(), hand_b.as_slice());
+
+        assert!(matches!(
+            mao.swap_hands(1, 3),
+            Err(Error::InvalidPlayerIndex {
+                player_index: 3,
+                len: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn retain_events() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![card(1, CommonCardType::Spade)]),
+                Player::new("p2".to_owned(), vec![card(1, CommonCardType::Heart)]),
+            ],
+            vec![Stack::new(
+                vec![card(5, CommonCardType::Heart)],
+                true,
+                vec![StackType::Playable],
+            )],
+        );
+        mao.set_config(Config {
+            retain_events: true,
+            ..Default::default()
+        });
+        assert!(mao.play_card(1, 0, Some(0)).unwrap().is_empty());
+        assert!(mao.event_history().is_empty());
+
+        // the turn of p2 ends when p1 plays
+        assert!(mao.play_card(0, 0, Some(0)).unwrap().is_empty());
+        assert_eq!(
+            mao.event_history(),
+            &[MaoEvent::PlayedCardEvent(CardEvent::new(
+                card(1, CommonCardType::Heart),
+                1,
+                Some(0),
+                0
+            ))]
+        );
+        assert_eq!(mao.players_events().len(), 1);
+    }
+
+    #[test]
+    fn invalid_mao_interaction_mismatch_at() {
+        let mut mao = mao_from_config("invalid_mao_interaction_mismatch_at", Config::default());
+        mao.players_mut().extend([
+            Player::new("p1".to_owned(), vec![]),
+            Player::new("p2".to_owned(), vec![card(3, CommonCardType::Diamond)]),
+        ]);
+        mao.on_action(MaoInteraction::new(
+            Some(IdString::Index(0)),
+            PlayerAction::SelectCard,
+        ));
+        let MaoInteractionResult::Leaf { func, .. } = mao.on_action(MaoInteraction::new(
+            Some(IdString::Index(1)),
+            PlayerAction::SelectPlayableStack,
+        )) else {
+            panic!("expected a leaf");
+        };
+        let interactions = [
+            MaoInteraction::new(Some(IdString::Index(0)), PlayerAction::SelectCard),
+            MaoInteraction::new(None, PlayerAction::SelectDrawableStack),
+        ];
+        let err = func(1, &mut mao, &interactions).unwrap_err();
+        let Some(Error::InvalidMaoInteraction {
+            mismatch_at,
+            received,
+            ..
+        }) = err.downcast_ref::<Error>()
+        else {
+            panic!("expected InvalidMaoInteraction, got {}", err);
+        };
+        assert_eq!(*mismatch_at, 1);
+        assert_eq!(received.len(), 2);
+        assert!(err.to_string().contains("mismatch at 1"));
+
+        let err = func(1, &mut mao, &interactions[..1]).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::InvalidMaoInteraction { mismatch_at: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn peek_draw_matches_next_draw() {
+        let mut mao = mao_from(
+            vec![],
+            vec![
+                Stack::new(vec![], false, vec![StackType::Drawable]),
+                Stack::new(
+                    vec![
+                        card(4, CommonCardType::Heart),
+                        card(9, CommonCardType::Club),
+                    ],
+                    false,
+                    vec![StackType::Drawable],
+                ),
+                Stack::new(
+                    vec![card(2, CommonCardType::Spade)],
+                    true,
+                    vec![StackType::Playable],
+                ),
+            ],
+        );
+        assert!(mao.peek_draw(Some(0)).unwrap().is_none());
+        assert!(matches!(
+            mao.peek_draw(Some(2)),
+            Err(Error::NoStackAvailable { .. })
+        ));
+        assert!(matches!(
+            mao.peek_draw(Some(3)),
+            Err(Error::InvalidStackIndex { .. })
+        ));
+
+        let peeked = mao.peek_draw(None).unwrap().unwrap().to_owned();
+        assert_eq!(mao.peek_draw(Some(1)).unwrap(), Some(&peeked));
+        let drawn = mao.draw_multiple_cards_unchosen(1).unwrap();
+        assert_eq!(drawn, vec![peeked]);
+        assert_eq!(
+            mao.peek_draw(None).unwrap(),
+            Some(&card(4, CommonCardType::Heart))
+        );
+    }
+
+    #[test]
+    fn dealer_plays_first() {
+        let mut mao = mao_from(
+            (1..=3)
+                .map(|i| Player::new(format!("p{}", i), vec![]))
+                .collect(),
+            vec![],
+        );
+        mao.set_dealer(0);
+        mao.start_game().unwrap();
+        assert_eq!(mao.player_turn(), 1);
+
+        mao.set_config(Config {
+            dealer_plays_first: true,
+            ..Default::default()
+        });
+        mao.start_game().unwrap();
+        assert_eq!(mao.player_turn(), 0);
+    }
+
+    #[test]
+    fn penalize_player() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new("p2".to_owned(), vec![card(5, CommonCardType::Heart)]),
+            ],
+            MaoCore::init_stacks().unwrap(),
+        );
+        mao.penalize(1, 2).unwrap();
+        assert_eq!(mao.players()[1].get_cards().len(), 3);
+        assert!(mao.players()[0].get_cards().is_empty());
+        assert!(matches!(
+            mao.penalize(2, 1),
+            Err(Error::InvalidPlayerIndex { .. })
+        ));
+    }
+
+    #[test]
+    fn playable_stack_count() {
+        let mao = mao_from_config(
+            "playable_stack_count",
+            Config {
+                playable_stack_count: 2,
+                ..Default::default()
+            },
+        );
+        let playable = mao.get_playable_stacks();
+        assert_eq!(playable.len(), 2);
+        assert_eq!(playable[0].1.len(), 1);
+        assert!(playable[1].1.is_empty());
+
+        let top = playable[0].1.top().unwrap().to_owned();
+        for (_, stack) in &playable {
+            assert!(matches!(
+                mao.can_place(&top, Some(stack)),
+                PlayerTurnResult::CanPlay
+            ));
+        }
+
+        let mut config = Config {
+            dirname: std::env::temp_dir().to_string_lossy().into_owned(),
+            playable_stack_count: 0,
+            ..Default::default()
+        };
+        assert!(matches!(config.verify(), Err(Error::InvalidConfig { .. })));
+    }
+
+    #[test]
+    fn undo_last_draw() {
+        let mut mao = mao_from_config("undo_last_draw", Config::default());
+        *mao.stacks_mut() = vec![
+            Stack::new(
+                vec![
+                    card(7, CommonCardType::Heart),
+                    card(3, CommonCardType::Club),
+                ],
+                false,
+                vec![StackType::Drawable],
+            ),
+            Stack::new(
+                vec![card(4, CommonCardType::Club)],
+                true,
+                vec![StackType::Playable],
+            ),
+        ];
+        mao.players_mut().extend([
+            Player::new("p1".to_owned(), vec![]),
+            Player::new("p2".to_owned(), vec![card(9, CommonCardType::Spade)]),
+        ]);
+        assert!(matches!(mao.undo_last_draw(), Err(Error::NothingToUndo)));
+
+        let drawable = mao.stacks()[0].get_cards().to_vec();
+        let hand = mao.players()[1].get_cards().to_vec();
+        let MaoInteractionResult::Leaf { interactions, func } =
+            mao.on_action(MaoInteraction::new(None, PlayerAction::SelectDrawableStack))
+        else {
+            panic!("drawing should be a leaf");
+        };
+        func(1, &mut mao, &interactions).unwrap();
+        assert_eq!(mao.players()[1].get_cards().len(), 2);
+        assert_eq!(mao.player_turn(), 0);
+
+        mao.undo_last_draw().unwrap();
+        assert_eq!(mao.players()[1].get_cards(), hand.as_slice());
+        assert_eq!(mao.stacks()[0].get_cards(), drawable.as_slice());
+        assert_eq!(mao.player_turn(), 1);
+        assert!(matches!(mao.undo_last_draw(), Err(Error::NothingToUndo)));
+    }
+
+    #[test]
+    fn new_for_testing_plays_card() {
+        let mut mao = MaoCore::new_for_testing(
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new(
+                    "p2".to_owned(),
+                    vec![
+                        card(8, CommonCardType::Spade),
+                        card(6, CommonCardType::Heart),
+                    ],
+                ),
+            ],
+            vec![
+                Stack::new(vec![], false, vec![StackType::Drawable]),
+                Stack::new(
+                    vec![card(6, CommonCardType::Club)],
+                    true,
+                    vec![StackType::Playable],
+                ),
+            ],
+        );
+        assert!(mao.available_rules().is_empty());
+        mao.on_action(MaoInteraction::new(
+            Some(IdString::Index(1)),
+            PlayerAction::SelectCard,
+        ));
+        let MaoInteractionResult::Leaf { interactions, func } = mao.on_action(MaoInteraction::new(
+            Some(IdString::Index(1)),
+            PlayerAction::SelectPlayableStack,
+        )) else {
+            panic!("playing a card should be a leaf");
+        };
+        assert!(func(1, &mut mao, &interactions).unwrap().is_empty());
+        assert_eq!(
+            mao.players()[1].get_cards(),
+            &[card(8, CommonCardType::Spade)]
+        );
+        assert_eq!(mao.stacks()[1].top(), Some(&card(6, CommonCardType::Heart)));
+        assert_eq!(mao.player_turn(), 0);
+    }
+
+    #[test]
+    fn single_stack_of() {
+        let mao = mao_from(
+            vec![],
+            vec![
+                Stack::new(vec![], false, vec![StackType::Drawable]),
+                Stack::new(vec![], true, vec![StackType::Playable]),
+                Stack::new(vec![], true, vec![StackType::Playable]),
+            ],
+        );
+        assert!(matches!(
+            mao.single_stack_of(StackType::Discardable),
+            Err(Error::NoStackAvailable { .. })
+        ));
+        assert_eq!(mao.single_stack_of(StackType::Drawable).unwrap().0, 0);
+        assert!(matches!(
+            mao.single_stack_of(StackType::Playable),
+            Err(Error::AmbiguousStack { count: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn same_card_ignores_metadata() {
+        let visible = card(6, CommonCardType::Diamond);
+        let mut hidden = visible.to_owned();
+        hidden.set_owner_can_see_it(false);
+        hidden.set_other_can_see_it(true);
+        assert_ne!(visible, hidden);
+        assert!(visible.same_card(&hidden));
+        assert!(!visible.same_card(&card(6, CommonCardType::Heart)));
+
+        let mao = mao_from(vec![Player::new("p1".to_owned(), vec![hidden])], vec![]);
+        assert_eq!(mao.find_card_holder(&visible), Some((0, 0)));
+    }
+
+    #[test]
+    fn play_multiple() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new(
+                    "p2".to_owned(),
+                    vec![
+                        card(8, CommonCardType::Heart),
+                        card(3, CommonCardType::Club),
+                        card(8, CommonCardType::Spade),
+                    ],
+                ),
+                Player::new("p3".to_owned(), vec![]),
+            ],
+            vec![
+                Stack::new(vec![], false, vec![StackType::Drawable]),
+                Stack::new(
+                    vec![card(8, CommonCardType::Diamond)],
+                    true,
+                    vec![StackType::Playable],
+                ),
+            ],
+        );
+        assert!(matches!(
+            mao.play_multiple(1, &[0, 2], Some(1)),
+            Err(Error::DisabledByConfig { .. })
+        ));
+        mao.set_config(Config {
+            allow_multiple_play: true,
+            ..Default::default()
+        });
+        assert!(matches!(
+            mao.play_multiple(1, &[0, 1], Some(1)),
+            Err(Error::OnMaoInteraction(_))
+        ));
+        assert_eq!(mao.players()[1].get_cards().len(), 3);
+
+        assert!(mao.play_multiple(1, &[0, 2], Some(1)).unwrap().is_empty());
+        assert_eq!(
+            mao.players()[1].get_cards(),
+            &[card(3, CommonCardType::Club)]
+        );
+        assert_eq!(mao.stacks()[1].len(), 3);
+        assert_eq!(mao.player_turn(), 2);
+    }
+
+    #[test]
+    fn stack_refilled() {
+        let mut mao = mao_from(
+            vec![],
+            vec![
+                Stack::new(vec![], false, vec![StackType::Drawable]),
+                Stack::new(
+                    vec![
+                        card(1, CommonCardType::Heart),
+                        card(2, CommonCardType::Heart),
+                        card(3, CommonCardType::Heart),
+                    ],
+                    true,
+                    vec![StackType::Playable],
+                ),
+                Stack::new(
+                    vec![card(4, CommonCardType::Club), card(5, CommonCardType::Club)],
+                    true,
+                    vec![StackType::Discardable],
+                ),
+            ],
+        );
+        let event = MaoEvent::StackRefilled {
+            stack_index: 0,
+            cards_added: 4,
+        };
+        assert!(!event.is_recordable());
+        assert_eq!(event.get_card(), None);
+
+        mao.refill_drawable_stacks(None, true).unwrap();
+        assert_eq!(mao.stacks()[0].len(), 4);
+        assert_eq!(mao.stacks()[1].len(), 1);
+        assert!(mao.stacks()[2].is_empty());
+    }
+
+    #[test]
+    fn silent_refill_released() {
+        let mut mao = MaoCore::new(
+            vec![Rule::from_fn("watch", watch_refills)],
+            vec![
+                Stack::new(vec![], false, vec![StackType::Drawable]),
+                Stack::new(
+                    vec![
+                        card(1, CommonCardType::Heart),
+                        card(2, CommonCardType::Heart),
+                    ],
+                    true,
+                    vec![StackType::Playable],
+                ),
+            ],
+            vec![],
+            Automaton::from_iter(Vec::<Vec<NodeState>>::new()),
+        );
+        mao.activate_rule("watch").unwrap();
+        mao.refill_drawable_stacks(None, false).unwrap();
+        assert_eq!(mao.stacks()[0].len(), 1);
+        assert_eq!(mao.rule_fire_counts(), HashMap::from([("watch", 1)]));
+
+        // nothing left to move
+        mao.refill_drawable_stacks(None, false).unwrap();
+        assert_eq!(mao.rule_fire_counts(), HashMap::from([("watch", 1)]));
+    }
+
+    #[test]
+    fn reset_round_accumulates_scores() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new(
+                    "p2".to_owned(),
+                    vec![
+                        card(8, CommonCardType::Heart),
+                        card(3, CommonCardType::Club),
+                    ],
+                ),
+            ],
+            vec![],
+        );
+        mao.reset_round().unwrap();
+        assert_eq!(mao.players()[0].score(), 0);
+        assert_eq!(mao.players()[1].score(), 11);
+        assert!(mao.players().iter().all(|p| p.get_cards().len() == 7));
+
+        let expected: Vec<isize> = mao
+            .players()
+            .iter()
+            .map(|p| {
+                p.score()
+                    + p.get_cards()
+                        .iter()
+                        .map(|c| c.get_value().point_value())
+                        .sum::<isize>()
+            })
+            .collect();
+        mao.reset_round().unwrap();
+        assert_eq!(
+            mao.players().iter().map(|p| p.score()).collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(CardValue::PlusInfinity.point_value(), 0);
+    }
+
+    #[test]
+    fn playable_card_indices() {
+        let mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![card(9, CommonCardType::Spade)]),
+                Player::new(
+                    "p2".to_owned(),
+                    vec![
+                        card(9, CommonCardType::Spade),
+                        card(4, CommonCardType::Diamond),
+                        card(2, CommonCardType::Club),
+                        card(9, CommonCardType::Heart),
+                    ],
+                ),
+            ],
+            vec![
+                Stack::new(vec![], false, vec![StackType::Drawable]),
+                Stack::new(
+                    vec![card(9, CommonCardType::Diamond)],
+                    true,
+                    vec![StackType::Playable],
+                ),
+                Stack::new(
+                    vec![card(3, CommonCardType::Heart)],
+                    true,
+                    vec![StackType::Playable],
+                ),
+            ],
+        );
+        assert_eq!(mao.playable_card_indices(1), vec![0, 1, 3]);
+        assert!(mao.playable_card_indices(0).is_empty());
+        assert!(mao.playable_card_indices(2).is_empty());
+    }
+
+    #[test]
+    fn deal_up_to() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new("p2".to_owned(), vec![card(1, CommonCardType::Club); 2]),
+                Player::new("p3".to_owned(), vec![card(2, CommonCardType::Club); 5]),
+            ],
+            vec![
+                Stack::new(
+                    vec![card(7, CommonCardType::Heart); 3],
+                    false,
+                    vec![StackType::Drawable],
+                ),
+                Stack::new(
+                    vec![card(5, CommonCardType::Spade); 5],
+                    true,
+                    vec![StackType::Discardable],
+                ),
+            ],
+        );
+        mao.deal_up_to(4).unwrap();
+        assert_eq!(
+            mao.players().iter().map(|p| p.len()).collect::<Vec<_>>(),
+            vec![4, 4, 5]
+        );
+        // the draw pile has been refilled with the discarded cards
+        assert_eq!(mao.stacks()[0].len() + mao.stacks()[1].len(), 2);
+    }
+
+    #[test]
+    fn previous_player() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new("p2".to_owned(), vec![card(4, CommonCardType::Heart)]),
+            ],
+            vec![Stack::new(
+                vec![card(4, CommonCardType::Club)],
+                true,
+                vec![StackType::Playable],
+            )],
+        );
+        assert_eq!(mao.previous_player(), None);
+        assert!(mao.previous_player_ref().is_none());
+
+        assert!(mao.play_card(1, 0, Some(0)).unwrap().is_empty());
+        assert_eq!(mao.player_turn(), 0);
+        assert_eq!(mao.previous_player(), Some(1));
+        assert_eq!(mao.previous_player_ref().unwrap().get_pseudo(), "p2");
+    }
+
+    #[test]
+    fn current_prompt() {
+        let mut mao = MaoCore::new_for_testing(
+            vec![Player::new(
+                "p1".to_owned(),
+                vec![card(3, CommonCardType::Heart)],
+            )],
+            MaoCore::init_stacks().unwrap(),
+        );
+        assert_eq!(mao.current_prompt(), None);
+        mao.on_action(MaoInteraction::new(
+            Some(IdString::Index(0)),
+            PlayerAction::SelectCard,
+        ));
+        let prompt = mao.current_prompt().unwrap();
+        assert!(prompt.contains("playable stack"), "{}", prompt);
+        assert!(prompt.starts_with("Select a "));
+    }
+
+    #[test]
+    fn recycle_discard_to_playable() {
+        let mut mao = mao_from(
+            vec![],
+            vec![
+                Stack::new(vec![], false, vec![StackType::Drawable]),
+                Stack::new(
+                    vec![card(6, CommonCardType::Heart)],
+                    true,
+                    vec![StackType::Playable],
+                ),
+                Stack::new(
+                    vec![
+                        card(1, CommonCardType::Club),
+                        card(2, CommonCardType::Club),
+                        card(3, CommonCardType::Club),
+                    ],
+                    true,
+                    vec![StackType::Discardable],
+                ),
+            ],
+        );
+        assert!(matches!(
+            mao.recycle_discard_to_playable(0),
+            Err(Error::NoStackAvailable { .. })
+        ));
+        assert!(matches!(
+            mao.recycle_discard_to_playable(3),
+            Err(Error::InvalidStackIndex { .. })
+        ));
+
+        assert_eq!(mao.recycle_discard_to_playable(1).unwrap(), 2);
+        assert_eq!(
+            mao.stacks()[1].get_cards(),
+            &[
+                card(1, CommonCardType::Club),
+                card(2, CommonCardType::Club),
+                card(6, CommonCardType::Heart)
+            ]
+        );
+        assert_eq!(
+            mao.stacks()[2].get_cards(),
+            &[card(3, CommonCardType::Club)]
+        );
+        assert!(mao.stacks()[0].is_empty());
+    }
+
+    #[test]
+    fn winner_pseudo() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("alice".to_owned(), vec![card(2, CommonCardType::Heart)]),
+                Player::new("bob".to_owned(), vec![card(5, CommonCardType::Spade)]),
+            ],
+            vec![],
+        );
+        assert_eq!(mao.winner_pseudo(), None);
+        mao.players_mut()[1].get_cards_mut().clear();
+        assert_eq!(mao.winner_pseudo().as_deref(), Some("bob"));
+    }
+
+    #[test]
+    fn hand_values() {
+        let mao = mao_from(
+            vec![Player::new(
+                "p1".to_owned(),
+                vec![
+                    card(12, CommonCardType::Heart),
+                    card(1, CommonCardType::Spade),
+                    Card::new(
+                        CardValue::PlusInfinity,
+                        CardType::Common(CommonCardType::Club),
+                        None,
+                    ),
+                ],
+            )],
+            vec![],
+        );
+        assert_eq!(
+            mao.hand_values(0).unwrap(),
+            vec![
+                CardValue::Number(12),
+                CardValue::Number(1),
+                CardValue::PlusInfinity
+            ]
+        );
+        assert_eq!(mao.hand_point_total(0).unwrap(), 13);
+        assert!(matches!(
+            mao.hand_point_total(1),
+            Err(Error::InvalidPlayerIndex { .. })
+        ));
+    }
+
+    #[test]
+    fn rng_mut_is_reproducible() {
+        // what a rule would do to pick a random player
+        fn random_target(mao: &mut MaoCore) -> usize {
+            let len = mao.players().len();
+            rand::Rng::gen_range(mao.rng_mut(), 0..len)
+        }
+        let new_mao = || {
+            let mut mao = mao_from(
+                (1..=5)
+                    .map(|i| Player::new(format!("p{}", i), vec![]))
+                    .collect(),
+                vec![],
+            );
+            mao.set_seed(42);
+            mao
+        };
+        let (mut first, mut second) = (new_mao(), new_mao());
+        let first_targets: Vec<usize> = (0..10).map(|_| random_target(&mut first)).collect();
+        let second_targets: Vec<usize> = (0..10).map(|_| random_target(&mut second)).collect();
+        assert_eq!(first_targets, second_targets);
+    }
+
+    #[test]
+    fn playable_and_discardable_stack() {
+        let mut stack = Stack::new(
+            vec![
+                card(1, CommonCardType::Heart),
+                card(2, CommonCardType::Heart),
+                card(3, CommonCardType::Heart),
+            ],
+            true,
+            vec![StackType::Playable],
+        );
+        assert!(stack.add_type(StackType::Discardable));
+        assert!(!stack.add_type(StackType::Discardable));
+        assert!(!stack.remove_type(&StackType::Drawable));
+        assert_eq!(
+            stack.get_stack_types(),
+            &[StackType::Playable, StackType::Discardable]
+        );
+
+        let mut mao = mao_from(
+            vec![],
+            vec![Stack::new(vec![], false, vec![StackType::Drawable]), stack],
+        );
+        assert_eq!(mao.recycle_discard_to_playable(1).unwrap(), 0);
+        assert_eq!(mao.stacks()[1].len(), 3);
+
+        mao.refill_drawable_stacks(None, true).unwrap();
+        assert_eq!(mao.stacks()[0].len(), 2);
+        assert_eq!(
+            mao.stacks()[1].get_cards(),
+            &[card(3, CommonCardType::Heart)]
+        );
+
+        assert!(mao.stacks_mut()[1].remove_type(&StackType::Playable));
+        assert_eq!(mao.stacks()[1].get_stack_types(), &[StackType::Discardable]);
+    }
+
+    #[test]
+    fn rule_activation_events() {
+        for event in [
+            MaoEvent::RuleActivated {
+                rule_name: String::from("seven"),
+            },
+            MaoEvent::RuleDeactivated {
+                rule_name: String::from("seven"),
+            },
+        ] {
+            assert!(!event.is_recordable());
+            assert!(!event.can_change_turn());
+            assert_eq!(event.get_card(), None);
+        }
+        let mut mao = mao_from(vec![], vec![]);
+        assert!(matches!(
+            mao.activate_rule_by_index(0),
+            Err(Error::InvalidRuleIndex { .. })
+        ));
+        let err = Error::RuleVetoed {
+            rule_name: String::from("seven"),
+            reason: Disallow::new(String::from("eight"), String::from("incompatible"), None)
+                .to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "The seven cannot be activated (eight: incompatible)"
+        );
+    }
+
+    #[test]
+    fn rule_activation_vetoed() {
+        let mut mao = MaoCore::new(
+            vec![
+                Rule::from_fn("guard", refuse_second),
+                Rule::from_fn("first", ignore_all),
+                Rule::from_fn("second", ignore_all),
+            ],
+            MaoCore::init_stacks().unwrap(),
+            vec![],
+            Automaton::from_iter(Vec::<Vec<NodeState>>::new()),
+        );
+        mao.activate_rule_by_index(0).unwrap();
+        mao.activate_rule_by_index(1).unwrap();
+        assert!(matches!(
+            mao.activate_rule_by_index(2),
+            Err(Error::RuleVetoed { rule_name, reason })
+                if rule_name == "second" && reason == "test: incompatible"
+        ));
+        assert_eq!(mao.activated_rules_indexes(), &[0, 1]);
+        assert_eq!(mao.rule_fire_counts(), HashMap::from([("guard", 1)]));
+
+        // the deactivation is seen by the rules but cannot be refused
+        mao.deactivate_rule_by_index(1).unwrap();
+        assert_eq!(mao.activated_rules_indexes(), &[0]);
+        assert_eq!(mao.rule_fire_counts(), HashMap::from([("guard", 2)]));
+    }
+
+    #[test]
+    fn players_count() {
+        let mut mao = mao_from(vec![], vec![]);
+        assert_eq!(mao.players_count(), 0);
+        mao.players_mut()
+            .extend((1..=3).map(|i| Player::new(format!("p{}", i), vec![])));
+        assert_eq!(mao.players_count(), 3);
+    }
+
+    #[test]
+    fn play_multiple_checks_say_once() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![card(2, CommonCardType::Club)]),
+                Player::new(
+                    "p2".to_owned(),
+                    vec![
+                        card(8, CommonCardType::Heart),
+                        card(8, CommonCardType::Spade),
+                        card(5, CommonCardType::Club),
+                    ],
+                ),
+                Player::new("p3".to_owned(), vec![card(8, CommonCardType::Club)]),
+            ],
+            vec![
+                Stack::new(
+                    vec![card(1, CommonCardType::Diamond); 5],
+                    false,
+                    vec![StackType::Drawable],
+                ),
+                Stack::new(
+                    vec![card(8, CommonCardType::Diamond)],
+                    true,
+                    vec![StackType::Playable],
+                ),
+            ],
+        );
+        mao.set_config(Config {
+            allow_multiple_play: true,
+            cards_effects: HashMap::from([(
+                CardEffectsKey::new(None, Some(CardValue::Number(8))),
+                SingOrMult::Single(SingleCardEffect::CardPlayerAction(CardPlayerAction::Say(
+                    vec![SingOrMult::Single("eight".to_owned())],
+                ))),
+            )]),
+            ..Default::default()
+        });
+
+        // nothing to check yet, the previous turn has no card
+        assert!(mao.play_multiple(1, &[0, 1], Some(1)).unwrap().is_empty());
+        assert_eq!(
+            mao.players()[1].get_cards(),
+            &[card(5, CommonCardType::Club)]
+        );
+        assert_eq!(mao.stacks()[1].len(), 3);
+        assert_eq!(mao.player_turn(), 2);
+        assert_eq!(mao.previous_player(), Some(1));
+
+        // the player who played the 8s without saying anything is the one penalized
+        let wrong_int = mao.play_card(2, 0, Some(1)).unwrap();
+        assert!(!wrong_int.is_empty());
+        assert!(wrong_int
+            .iter()
+            .all(|int| matches!(int, WrongPlayerInteraction::ForgotSomething(_))));
+        assert_eq!(mao.players()[0].len(), 1);
+        assert_eq!(mao.players()[1].len(), 1 + wrong_int.len());
+    }
+
+    #[test]
+    fn jump_in_checks_previous_player() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![card(8, CommonCardType::Heart)]),
+                Player::new("p2".to_owned(), vec![card(8, CommonCardType::Heart)]),
+                Player::new("p3".to_owned(), vec![card(4, CommonCardType::Club)]),
+            ],
+            vec![
+                Stack::new(
+                    vec![card(1, CommonCardType::Diamond); 5],
+                    false,
+                    vec![StackType::Drawable],
+                ),
+                Stack::new(
+                    vec![card(8, CommonCardType::Club)],
+                    true,
+                    vec![StackType::Playable],
+                ),
+            ],
+        );
+        mao.set_config(Config {
+            allow_jump_in: true,
+            cards_effects: HashMap::from([(
+                CardEffectsKey::new(None, Some(CardValue::Number(8))),
+                SingOrMult::Single(SingleCardEffect::CardPlayerAction(CardPlayerAction::Say(
+                    vec![SingOrMult::Single("eight".to_owned())],
+                ))),
+            )]),
+            ..Default::default()
+        });
+        // p2 plays its 8 without saying "eight"
+        assert!(mao.play_card(1, 0, Some(1)).unwrap().is_empty());
+        assert_eq!(mao.player_turn(), 2);
+
+        // p1 jumps in, p2 is penalized and not p3 whose turn has been skipped
+        let wrong_int = mao.jump_in(0, 0).unwrap();
+        assert_eq!(wrong_int.len(), 1);
+        assert!(matches!(
+            wrong_int[0],
+            WrongPlayerInteraction::ForgotSomething(_)
+        ));
+        assert!(mao.players()[0].is_empty());
+        assert_eq!(mao.players()[1].len(), 1);
+        assert_eq!(mao.players()[2].len(), 1);
+        assert_eq!(mao.previous_player(), Some(0));
+        assert_eq!(mao.player_turn(), 1);
+    }
+
+    #[test]
+    fn pass_turn_checks_previous_player() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![card(4, CommonCardType::Club)]),
+                Player::new("p2".to_owned(), vec![card(8, CommonCardType::Heart)]),
+                Player::new("p3".to_owned(), vec![card(4, CommonCardType::Club)]),
+            ],
+            vec![
+                Stack::new(
+                    vec![card(1, CommonCardType::Diamond); 5],
+                    false,
+                    vec![StackType::Drawable],
+                ),
+                Stack::new(
+                    vec![card(8, CommonCardType::Club)],
+                    true,
+                    vec![StackType::Playable],
+                ),
+            ],
+        );
+        mao.set_config(Config {
+            allow_pass: true,
+            cards_effects: HashMap::from([(
+                CardEffectsKey::new(None, Some(CardValue::Number(8))),
+                SingOrMult::Single(SingleCardEffect::CardPlayerAction(CardPlayerAction::Say(
+                    vec![SingOrMult::Single("eight".to_owned())],
+                ))),
+            )]),
+            ..Default::default()
+        });
+        // p2 plays its 8 without saying "eight"
+        assert!(mao.play_card(1, 0, Some(1)).unwrap().is_empty());
+        assert_eq!(mao.player_turn(), 2);
+
+        // p3 passes, p2 is penalized and not the passer
+        let wrong_int = mao.pass_turn(2).unwrap();
+        assert_eq!(wrong_int.len(), 1);
+        assert!(matches!(
+            wrong_int[0],
+            WrongPlayerInteraction::ForgotSomething(_)
+        ));
+        assert_eq!(mao.players()[1].len(), 1);
+        assert_eq!(mao.players()[2].len(), 1);
+        assert_eq!(mao.player_turn(), 0);
+        assert_eq!(mao.previous_player(), Some(2));
+
+        // the 8 has already been checked
+        assert!(mao.pass_turn(0).unwrap().is_empty());
+        assert_eq!(mao.players()[1].len(), 1);
+        assert_eq!(mao.players()[2].len(), 1);
+        assert_eq!(mao.player_turn(), 1);
+    }
+
+    #[test]
+    fn retain_events_once() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![card(1, CommonCardType::Spade)]),
+                Player::new("p2".to_owned(), vec![card(1, CommonCardType::Heart)]),
+                Player::new("p3".to_owned(), vec![card(1, CommonCardType::Club)]),
+            ],
+            vec![Stack::new(
+                vec![card(5, CommonCardType::Heart)],
+                true,
+                vec![StackType::Playable],
+            )],
+        );
+        mao.set_config(Config {
+            retain_events: true,
+            ..Default::default()
+        });
+        assert!(mao.play_card(1, 0, Some(0)).unwrap().is_empty());
+        assert!(mao.on_say_action(2, "hello".to_owned()).unwrap().is_empty());
+        // the message of p3 is kept in the events of its turn
+        assert!(mao.play_card(2, 0, Some(0)).unwrap().is_empty());
+        assert!(mao.play_card(0, 0, Some(0)).unwrap().is_empty());
+
+        let said = MaoEvent::SayEvent {
+            message: "hello".to_owned(),
+            player_index: 2,
+        };
+        assert_eq!(
+            mao.event_history()
+                .iter()
+                .filter(|event| **event == said)
+                .count(),
+            1
+        );
+        assert_eq!(
+            mao.event_history()
+                .iter()
+                .filter(|event| matches!(event, MaoEvent::PlayedCardEvent(_)))
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn config_merge_all_values() {
+        let mut base = Config {
+            allow_pass: true,
+            wrong_turn_penality: 3,
+            ..Default::default()
+        };
+        // a default config does not change anything
+        base.merge(Config::default());
+        assert!(base.allow_pass);
+        assert_eq!(base.wrong_turn_penality, 3);
+
+        base.merge(Config {
+            penalize_out_of_turn_say: true,
+            allow_jump_in: true,
+            wrong_turn_penality: 2,
+            wrong_card_penality: 4,
+            initial_hand_size: 5,
+            penality_target: PenaltyTarget::Stack(1),
+            initial_direction: -1,
+            infinity_wild: true,
+            retain_events: true,
+            dealer_plays_first: true,
+            playable_stack_count: 2,
+            allow_multiple_play: true,
+            ..Default::default()
+        });
+        assert!(base.penalize_out_of_turn_say);
+        assert!(base.allow_jump_in);
+        assert!(base.allow_pass);
+        assert_eq!(base.wrong_turn_penality, 2);
+        assert_eq!(base.wrong_card_penality, 4);
+        assert_eq!(base.initial_hand_size, 5);
+        assert_eq!(base.penality_target, PenaltyTarget::Stack(1));
+        assert_eq!(base.initial_direction, -1);
+        assert!(base.infinity_wild);
+        assert!(base.retain_events);
+        assert!(base.dealer_plays_first);
+        assert_eq!(base.playable_stack_count, 2);
+        assert!(base.allow_multiple_play);
+    }
+
+    #[test]
+    fn hand_limit_on_every_draw() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![card(2, CommonCardType::Spade); 2]),
+                Player::new("p2".to_owned(), vec![]),
+            ],
+            vec![
+                Stack::new(
+                    vec![card(1, CommonCardType::Diamond); 10],
+                    false,
+                    vec![StackType::Drawable],
+                ),
+                Stack::new(vec![], true, vec![StackType::Discardable]),
+            ],
+        );
+        mao.set_config(Config {
+            max_hand_size: Some(2),
+            ..Default::default()
+        });
+        mao.penalize(0, 1).unwrap();
+        assert_eq!(mao.players()[0].len(), 2);
+        assert_eq!(mao.stacks()[1].len(), 1);
+
+        mao.draw_specific_card(0, 0, |_| true).unwrap();
+        assert_eq!(mao.players()[0].len(), 2);
+        assert_eq!(mao.stacks()[1].len(), 2);
+
+        // the discarded cards may go back to the drawable stack while dealing
+        mao.deal_up_to(3).unwrap();
+        assert_eq!(mao.players()[0].len(), 2);
+        assert_eq!(mao.players()[1].len(), 2);
+        assert_eq!(
+            mao.stacks().iter().map(|stack| stack.len()).sum::<usize>(),
+            8
+        );
+    }
+
+    #[test]
+    fn play_multiple_in_given_order() {
+        let mut mao = mao_from(
+            vec![
+                Player::new("p1".to_owned(), vec![]),
+                Player::new(
+                    "p2".to_owned(),
+                    vec![
+                        card(8, CommonCardType::Heart),
+                        card(8, CommonCardType::Spade),
+                        card(3, CommonCardType::Club),
+                    ],
+                ),
+                Player::new("p3".to_owned(), vec![]),
+            ],
+            vec![
+                Stack::new(
+                    vec![card(1, CommonCardType::Diamond); 5],
+                    false,
+                    vec![StackType::Drawable],
+                ),
+                Stack::new(
+                    vec![card(5, CommonCardType::Heart)],
+                    true,
+                    vec![StackType::Playable],
+                ),
+            ],
+        );
+        mao.set_config(Config {
+            allow_multiple_play: true,
+            ..Default::default()
+        });
+        // the 8 of heart matches the 5 of heart, the 8 of spade follows it
+        assert!(mao.play_multiple(1, &[0, 1], Some(1)).unwrap().is_empty());
+        assert_eq!(
+            mao.players()[1].get_cards(),
+            &[card(3, CommonCardType::Club)]
+        );
+        assert_eq!(
+            mao.stacks()[1].get_cards(),
+            &[
+                card(5, CommonCardType::Heart),
+                card(8, CommonCardType::Heart),
+                card(8, CommonCardType::Spade),
+            ]
+        );
+        assert_eq!(mao.player_turn(), 2);
+    }
+
+    #[test]
+    fn hand_limit_without_discard() {
+        let mut mao = mao_from(
+            vec![Player::new(
+                "p1".to_owned(),
+                vec![card(2, CommonCardType::Spade); 2],
+            )],
+            vec![Stack::new(
+                vec![card(1, CommonCardType::Diamond); 5],
+                false,
+                vec![StackType::Drawable],
+            )],
+        );
+        mao.set_config(Config {
+            max_hand_size: Some(2),
+            ..Default::default()
+        });
+        assert!(matches!(
+            mao.draw_specific_card(0, 0, |_| true),
+            Err(Error::NoStackAvailable { .. })
+        ));
+        // the drawn card is kept rather than lost
+        assert_eq!(mao.players()[0].len(), 3);
+        assert_eq!(mao.stacks()[0].len(), 4);
+    }
 }
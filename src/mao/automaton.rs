@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use indextree::{Arena, NodeId};
 
 use crate::{
@@ -104,11 +106,54 @@ impl NodeState {
     }
 }
 
+/// Builds the path of [`NodeState`]s of an action from its [`PlayerAction`]s,
+/// only the executable leaf holds the rule which adds the path
+#[derive(Debug, Clone, Default)]
+pub struct NodeStatePathBuilder {
+    actions: Vec<PlayerAction>,
+    rule: Option<String>,
+}
+
+impl NodeStatePathBuilder {
+    pub fn new(actions: &[PlayerAction]) -> Self {
+        Self {
+            actions: actions.to_vec(),
+            rule: None,
+        }
+    }
+
+    /// Tags the path with the rule `rule`
+    pub fn rule(mut self, rule: &str) -> Self {
+        self.rule = Some(rule.to_owned());
+        self
+    }
+
+    /// Returns the path, the last action executes `func`, the path is empty if there is no action
+    pub fn build(self, func: CallbackInteraction) -> Vec<NodeState> {
+        let len = self.actions.len();
+        let mut rule = self.rule;
+        self.actions
+            .into_iter()
+            .enumerate()
+            .map(|(i, action)| {
+                let interaction = MaoInteraction::new(None, action);
+                if i + 1 == len {
+                    NodeState::new(interaction, Some(func), rule.take())
+                } else {
+                    NodeState::new(interaction, None, None)
+                }
+            })
+            .collect()
+    }
+}
+
 #[derive(Clone)]
 pub struct Automaton {
     arena: Arena<NodeState>,
     current_state: NodeId,
     root: NodeId,
+    /// the interactions of the last action which reached a leaf
+    last_interactions: Vec<MaoInteraction>,
 }
 
 impl std::fmt::Debug for Automaton {
@@ -240,6 +285,7 @@ impl Automaton {
                                 .map(|&v| v.action.to_owned())
                                 .collect();
                             interactions.push(interaction);
+                            self.last_interactions = interactions.to_owned();
                             Ok(MaoInteractionResult::Leaf { interactions, func })
                         }
                         // is a node so advance in it
@@ -299,6 +345,7 @@ impl Automaton {
                         .collect();
                     interactions.push(interaction);
                     self.reset();
+                    self.last_interactions = interactions.to_owned();
                     return MaoInteractionResult::Leaf {
                         func: self
                             .arena
@@ -340,6 +387,19 @@ impl Automaton {
         self.current_state = self.root;
     }
 
+    /// Goes back to the root and forgets the last completed action,
+    /// returns the interactions of the action which was in progress
+    pub fn abort(&mut self) -> Vec<MaoInteraction> {
+        let interactions = self
+            .get_executed_actions()
+            .into_iter()
+            .map(|node| node.action.to_owned())
+            .collect();
+        self.reset();
+        self.last_interactions.clear();
+        interactions
+    }
+
     /// Returns the current state, returning None if no action has been done yet
     ///
     /// # Panics
@@ -364,6 +424,18 @@ impl Automaton {
         }
     }
 
+    /// Returns the distinct actions which can follow the current state, in insertion order
+    pub fn next_actions(&self) -> Vec<PlayerAction> {
+        let mut actions: Vec<PlayerAction> = Vec::new();
+        for id in self.children_of(self.current_state) {
+            let action = &self.arena.get(id).unwrap().get().action.action;
+            if !actions.contains(action) {
+                actions.push(action.to_owned());
+            }
+        }
+        actions
+    }
+
     /// Returns the executed actions of this [`Automaton`], ordered by time
     ///
     /// # Panics
@@ -380,6 +452,42 @@ impl Automaton {
         a
     }
 
+    /// Returns the interactions (with their data) of the action in progress,
+    /// or the ones of the last completed action if none is in progress
+    pub fn executed_interactions(&self) -> Vec<MaoInteraction> {
+        if self.current_state == self.root {
+            self.last_interactions.to_owned()
+        } else {
+            self.get_executed_actions()
+                .into_iter()
+                .map(|node| node.action.to_owned())
+                .collect()
+        }
+    }
+
+    /// Returns all the paths from the root to a leaf which has been added by `rule`
+    ///
+    /// Only the leaves keep the rule which added them, so the rule of the leaf is used
+    pub fn paths_for_rule(&self, rule: &str) -> Vec<Vec<NodeState>> {
+        self.root
+            .descendants(&self.arena)
+            .filter(|&id| {
+                let node = self.arena.get(id).unwrap().get();
+                node.func.is_some() && node.rule.as_deref() == Some(rule)
+            })
+            .map(|id| {
+                let mut path: Vec<NodeState> = id
+                    .ancestors(&self.arena)
+                    .map(|id| self.arena.get(id).unwrap().get().to_owned())
+                    .collect();
+                // remove root
+                path.pop();
+                path.reverse();
+                path
+            })
+            .collect()
+    }
+
     fn get_node_id_from_children(&self, node_id: NodeId, node_state: &NodeState) -> Option<NodeId> {
         self.children_of(node_id)
             .iter()
@@ -400,16 +508,153 @@ impl Automaton {
             .cloned()
     }
 
+    /// Moves the current state to the node reached by following `path` from the root,
+    /// the current state is left untouched if the path cannot be followed
+    ///
+    /// Only nodes can be reached, an executable leaf cannot be part of `path`
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if an action of `path` does not lead to a node
+    pub fn set_current_path(&mut self, path: &[PlayerAction]) -> Result<(), Error> {
+        let mut current = self.root;
+        for action in path {
+            current =
+                self.get_node_id_of(current, action.to_owned())
+                    .ok_or(Error::OnMaoInteraction(format!(
+                        "Cannot follow the action {} while restoring the current state",
+                        action
+                    )))?;
+        }
+        self.current_state = current;
+        Ok(())
+    }
+
+    /// Returns true if `path` can be followed from the current state up to an executable leaf
     pub fn path_exists(&self, path: &[PlayerAction]) -> bool {
-        let mut current = self.current_state;
-        for action in &path[..path.len().saturating_sub(1)] {
+        self.path_exists_from(self.current_state, path)
+    }
+
+    /// Returns true if `path` is a full interaction from the root, including its executable leaf
+    pub fn path_exists_full(&self, path: &[PlayerAction]) -> bool {
+        self.path_exists_from(self.root, path)
+    }
+
+    fn path_exists_from(&self, mut current: NodeId, path: &[PlayerAction]) -> bool {
+        let Some((leaf_action, node_actions)) = path.split_last() else {
+            return false;
+        };
+        for action in node_actions {
             if let Some(node_id) = self.get_node_id_of(current, action.to_owned()) {
                 current = node_id;
             } else {
                 return false;
             }
         }
-        true
+        self.get_leaves(current)
+            .iter()
+            .any(|&id| &self.arena.get(id).unwrap().get().action.action == leaf_action)
+    }
+
+    /// Returns the rule which the full interaction `path` (from the root) depends on
+    ///
+    /// `None` is returned if the path does not exist or if it can be done without any rule
+    pub fn action_requires_rule(&self, path: &[PlayerAction]) -> Option<&str> {
+        let (leaf_action, node_actions) = path.split_last()?;
+        let mut current = vec![self.root];
+        for action in node_actions {
+            current = current
+                .iter()
+                .flat_map(|&id| self.children_of(id))
+                .filter(|&id| {
+                    let node = self.arena.get(id).unwrap().get();
+                    &node.action.action == action && node.func.is_none()
+                })
+                .collect();
+        }
+        let rules: Vec<Option<&str>> = current
+            .iter()
+            .flat_map(|&id| self.get_leaves(id))
+            .map(|id| self.arena.get(id).unwrap().get())
+            .filter(|node| &node.action.action == leaf_action)
+            .map(|node| node.rule.as_deref())
+            .collect();
+        if rules.iter().any(|rule| rule.is_none()) {
+            return None;
+        }
+        rules.into_iter().flatten().next()
+    }
+
+    /// Checks that `paths` can be used to build an [`Automaton`]:
+    /// each path ends with the only executable node and two paths do not lead to the same leaf
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidAutomaton`] describing the first invalid path
+    pub fn validate_paths<V>(paths: &[V]) -> Result<(), Error>
+    where
+        V: AsRef<Vec<NodeState>>,
+    {
+        let mut leaves: Vec<(Vec<&PlayerAction>, &NodeState)> = Vec::with_capacity(paths.len());
+        for (i, path) in paths.iter().enumerate() {
+            let Some((leaf, nodes)) = path.as_ref().split_last() else {
+                return Err(Error::InvalidAutomaton {
+                    desc: format!("the path {} is empty", i),
+                });
+            };
+            if leaf.func.is_none() || nodes.iter().any(|node| node.func.is_some()) {
+                return Err(Error::InvalidAutomaton {
+                    desc: format!("only the last node of the path {} has to be executable", i),
+                });
+            }
+            let key = (nodes.iter().map(|node| &node.action.action).collect(), leaf);
+            if leaves.contains(&key) {
+                return Err(Error::InvalidAutomaton {
+                    desc: format!("the path {} is already present ({:?})", i, leaf),
+                });
+            }
+            leaves.push(key);
+        }
+        Ok(())
+    }
+
+    /// Checks that the [`Automaton`] is well formed: the executable nodes are the leaves
+    /// and two sibling leaves are different
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidAutomaton`] describing the first malformed node
+    pub fn validate(&self) -> Result<(), Error> {
+        for id in self.root.descendants(&self.arena).skip(1) {
+            if id.is_removed(&self.arena) {
+                continue;
+            }
+            let node = self.arena.get(id).unwrap().get();
+            let has_children = !self.children_of(id).is_empty();
+            if node.func.is_some() == has_children {
+                return Err(Error::InvalidAutomaton {
+                    desc: format!(
+                        "the node {:?} has to be either a leaf or not executable",
+                        node
+                    ),
+                });
+            }
+            let leaves: Vec<&NodeState> = self
+                .get_leaves(id)
+                .iter()
+                .map(|&id| self.arena.get(id).unwrap().get())
+                .collect();
+            if leaves
+                .iter()
+                .enumerate()
+                .any(|(i, leaf)| leaves[..i].contains(leaf))
+            {
+                return Err(Error::InvalidAutomaton {
+                    desc: format!("the node {:?} has duplicated leaves", node),
+                });
+            }
+        }
+        Ok(())
     }
 
     fn verify_action_path(datas: &[NodeState]) {
@@ -449,11 +694,16 @@ impl Automaton {
         }
     }
 
-    pub fn remove_paths<T>(&mut self, paths: T)
+    /// Removes the given paths from this [`Automaton`], nodes still used by other paths are kept,
+    /// the nodes which became unreachable are pruned (see [`Self::prune_unreachable`])
+    ///
+    /// Returns the number of nodes which have been removed
+    pub fn remove_paths<T>(&mut self, paths: T) -> usize
     where
         T: IntoIterator,
         T::Item: AsMut<Vec<NodeState>> + AsRef<Vec<NodeState>>,
     {
+        let mut removed = 0;
         for mut path in paths.into_iter() {
             if path.as_ref().is_empty() {
                 continue;
@@ -466,10 +716,62 @@ impl Automaton {
                 for node_id in node_ids.iter().rev() {
                     if self.children_of(*node_id).is_empty() {
                         node_id.remove(&mut self.arena);
+                        removed += 1;
                     }
                 }
             }
         }
+        removed + self.prune_unreachable()
+    }
+
+    /// Removes the nodes which cannot lead to an executable leaf, that is the nodes detached from the root
+    /// and the non executable nodes without children
+    ///
+    /// The current state goes back to the root if it has been pruned
+    ///
+    /// Returns the number of nodes which have been pruned
+    pub fn prune_unreachable(&mut self) -> usize {
+        let reachable: HashSet<NodeId> = self.root.descendants(&self.arena).collect();
+        let detached: Vec<NodeId> = self
+            .arena
+            .iter_node_ids()
+            .filter(|id| !reachable.contains(id))
+            .collect();
+        let mut pruned = 0;
+        for id in detached {
+            if !id.is_removed(&self.arena) {
+                id.remove(&mut self.arena);
+                pruned += 1;
+            }
+        }
+        loop {
+            let dead_ends: Vec<NodeId> = self
+                .root
+                .descendants(&self.arena)
+                .skip(1)
+                .filter(|&id| {
+                    self.arena.get(id).unwrap().get().func.is_none()
+                        && self.children_of(id).is_empty()
+                })
+                .collect();
+            if dead_ends.is_empty() {
+                break;
+            }
+            for id in dead_ends {
+                id.remove(&mut self.arena);
+                pruned += 1;
+            }
+        }
+        if self.current_state.is_removed(&self.arena) {
+            self.reset();
+        }
+        pruned
+    }
+
+    /// Returns true if both [`Automaton`]s have the same shape (actions and leaves),
+    /// without taking into account the rules which added the paths
+    pub fn structurally_eq(&self, other: &Self) -> bool {
+        self.same_node(self.root, (other, other.root), false)
     }
 
     fn children_of(&self, node_id: NodeId) -> Vec<NodeId> {
@@ -479,7 +781,14 @@ impl Automaton {
             .collect()
     }
 
-    fn same_node(&self, self_node_id: NodeId, (other, node_id): (&Self, NodeId)) -> bool {
+    /// Compares recursively the children of `self_node_id` and `node_id`,
+    /// the `rule` of the leaves is compared only if `check_rules` is true
+    fn same_node(
+        &self,
+        self_node_id: NodeId,
+        (other, node_id): (&Self, NodeId),
+        check_rules: bool,
+    ) -> bool {
         let (self_actions, other_actions): (Vec<NodeId>, Vec<NodeId>) = (
             self.children_of(self_node_id).into_iter().collect(),
             other.children_of(node_id).into_iter().collect(),
@@ -506,7 +815,7 @@ impl Automaton {
             }
             if s_node.func.is_some() {
                 // there are leaves (exectable nodes)
-                if s_node != o_node {
+                if (check_rules && s_node != o_node) || s_node.action != o_node.action {
                     return false;
                 }
             } else {
@@ -515,7 +824,7 @@ impl Automaton {
                     return false;
                 }
             }
-            if !self.same_node(*s_id, (other, *o_id)) {
+            if !self.same_node(*s_id, (other, *o_id), check_rules) {
                 return false;
             }
         }
@@ -534,6 +843,7 @@ where
             arena,
             current_state: root,
             root,
+            last_interactions: Vec::new(),
         };
         for mut datas in iter.into_iter() {
             Self::verify_action_path(datas.as_ref());
@@ -559,6 +869,6 @@ where
 
 impl PartialEq for Automaton {
     fn eq(&self, other: &Self) -> bool {
-        self.same_node(self.root, (other, other.root))
+        self.same_node(self.root, (other, other.root), true)
     }
 }
@@ -1,8 +1,9 @@
 use core::result::Result;
 
-use rand::{seq::SliceRandom, thread_rng};
-use serde::Deserialize;
+use rand::{rngs::StdRng, seq::SliceRandom, thread_rng, SeedableRng};
+use serde::{Deserialize, Serialize};
 use std::{
+    collections::HashMap,
     fs::{self, OpenOptions},
     io::Write,
     ops::{DerefMut, Range},
@@ -12,12 +13,16 @@ use std::{
 
 use crate::{
     card::{card_type::CardType, card_value::CardValue, common_card_type::CommonCardType, Card},
-    config::{CardEffectsKey, CardPlayerAction, Config, SingOrMult, SingleCardEffect},
+    config::{
+        CardEffectsKey, CardPlayerAction, Config, PenaltyTarget, SingOrMult, SingleCardEffect,
+    },
+    data::{Data, DataContainer},
     error::{DmDescription, Error},
     mao_event::{
         card_event::CardEvent,
         mao_event_result::{
-            CallbackFunction, Disallow, MaoEventResult, MaoEventResultType, WrongPlayerInteraction,
+            CallbackFunction, Disallow, GivePenality, MaoEventResult, MaoEventResultType,
+            WrongPlayerInteraction,
         },
         MaoEvent, StackTarget,
     },
@@ -135,6 +140,55 @@ impl Default for PlayerTurnChange {
     }
 }
 
+/// What a viewer can see of the hand of a [`Player`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlayerHandSummary {
+    pub pseudo: String,
+    pub card_count: usize,
+    /// `None` for the cards hidden to the viewer
+    pub visible_cards: Vec<Option<Card>>,
+}
+
+/// What a viewer can see of a [`Stack`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PublicStackState {
+    pub stack_types: Vec<StackType>,
+    pub visible: bool,
+    pub card_count: usize,
+    /// the top card, `None` if the stack is not visible or empty
+    pub top: Option<Card>,
+}
+
+/// A snapshot of the game holding only what a viewer is allowed to see
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PublicGameState {
+    pub hands: Vec<PlayerHandSummary>,
+    pub stacks: Vec<PublicStackState>,
+    pub player_turn: usize,
+    /// the direction of the turn (-1 OR 1)
+    pub turn: isize,
+    pub activated_rules: Vec<String>,
+}
+
+/// The informations of a [`Rule`] which can be displayed to the players
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleInfo {
+    pub name: String,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    pub active: bool,
+}
+
+/// The effects that a [`Card`] would have if the current player played it
+#[derive(Debug, Clone)]
+pub struct CardEffectPreview {
+    pub turn_changes: Vec<PlayerTurnChange>,
+    pub player_actions: Vec<CardPlayerAction>,
+    /// true if the direction of the turn would be reversed
+    pub reverses_direction: bool,
+    pub next_player: usize,
+}
+
 pub enum MaoActionResult {
     TurnAction {
         result: Vec<MaoEventResult>,
@@ -143,7 +197,7 @@ pub enum MaoActionResult {
     Nothing,
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub enum PlayerTurnResult {
     CanPlay,
     WrongTurn,
@@ -200,6 +254,10 @@ pub struct UiCallbacks {
     pub prompt_coords: fn() -> anyhow::Result<Coords>,
 }
 
+/// The state of a game of Mao
+///
+/// [`MaoCore`] is [`Send`] and [`Sync`] (the loaded [`Rule`]s only hold function pointers),
+/// so it can be shared between threads behind a [`std::sync::Mutex`]
 pub struct MaoCore {
     available_rules: Vec<Rule>,
     activated_rules: Vec<usize>,
@@ -217,6 +275,18 @@ pub struct MaoCore {
     dealer: usize,
     config: Config,
     possible_actions: Vec<String>,
+    /// the data stored by the rules for the whole game
+    game_data: Data,
+    /// the random generator used by the game, can be seeded for reproducible games
+    rng: StdRng,
+    /// the number of non ignored results returned by each rule (index of the rule inside `available_rules`)
+    rule_fire_counts: HashMap<usize, usize>,
+    /// the events removed from `player_events` at the end of the turns, ordered by time
+    /// (only filled if `retain_events` is enabled in the [`Config`])
+    event_history: Vec<MaoEvent>,
+    /// the last draw with the (player_turn, turn) before it,
+    /// only kept while it is the most recent recordable event
+    last_draw: Option<(CardEvent, usize, isize)>,
 }
 
 // getters and setters
@@ -233,42 +303,115 @@ impl MaoCore {
     pub fn available_rules(&self) -> &[Rule] {
         &self.available_rules
     }
+    /// Draws a penality card for the player `player_index`, the card goes
+    /// where the [`PenaltyTarget`] of the [`Config`] says
     pub fn common_penality_to_player(&mut self, player_index: usize) -> Result<(), Error> {
+        if player_index >= self.players.len() {
+            return Err(Error::InvalidPlayerIndex {
+                player_index,
+                len: self.players.len(),
+            });
+        }
+        let target = match self.config.penality_target {
+            PenaltyTarget::Hand => StackTarget::Player(player_index),
+            PenaltyTarget::Stack(stack_index) => {
+                if stack_index >= self.stacks.len() {
+                    return Err(Error::InvalidStackIndex {
+                        stack_index,
+                        len: self.stacks.len(),
+                    });
+                }
+                StackTarget::Stack(stack_index)
+            }
+        };
         let card = self.draw_multiple_cards_unchosen(1)?.pop().unwrap();
-        let len = self.players.len();
-        match self.players.get_mut(player_index) {
-            Some(player) => {
-                player.get_cards_mut().push(card);
+        match target {
+            StackTarget::Player(player_index) => self.add_cards_to_hand(player_index, [card]),
+            StackTarget::Stack(_) => {
+                self.get_stack_target(target)?.add_card(card);
                 Ok(())
             }
-            None => Err(Error::InvalidPlayerIndex { player_index, len }),
         }
     }
-    fn correct_player_action<I>(&self, expected: &[PlayerAction], datas: I) -> bool
+    /// Gives `cards` penality cards to the player `player_index` without going through the rules
+    /// and releases a [`MaoEvent::PenaltyApplied`]
+    pub fn penalize(&mut self, player_index: usize, cards: usize) -> Result<(), Error> {
+        for _ in 0..cards {
+            self.common_penality_to_player(player_index)?;
+        }
+        self.on_event(&MaoEvent::PenaltyApplied {
+            player_index,
+            cards_drawn: cards,
+        })?;
+        Ok(())
+    }
+    /// Checks that the actions of `datas` are exactly the `expected` ones
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`Error::InvalidMaoInteraction`] with the index of the first action which differs
+    fn correct_player_action<I>(&self, expected: &[PlayerAction], datas: I) -> Result<(), Error>
     where
         I: IntoIterator,
         I::Item: Into<PlayerAction>,
     {
         let datas: Vec<PlayerAction> = datas.into_iter().map(|v| v.into()).collect();
-        if expected.len() != datas.len() {
-            return false;
-        }
-        if expected.iter().zip(datas).any(|(ver, data)| ver != &data) {
-            return false;
+        let mismatch_at = expected
+            .iter()
+            .zip(&datas)
+            .position(|(ver, data)| ver != data)
+            .or((expected.len() != datas.len()).then(|| expected.len().min(datas.len())));
+        match mismatch_at {
+            Some(mismatch_at) => Err(Error::InvalidMaoInteraction {
+                expected: expected.to_vec(),
+                received: datas,
+                mismatch_at,
+            }),
+            None => Ok(()),
         }
-        true
+    }
+    pub fn config(&self) -> &Config {
+        &self.config
     }
     pub fn dealer(&self) -> usize {
         self.dealer
     }
 
-    pub fn on_say_action(&mut self, player_index: usize, message: String) -> anyhow::Result<()> {
+    /// Seeds the random generator of the game so that the following random operations are reproducible
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// Returns the random generator of the game, the rules should use it
+    /// instead of their own so that a seeded game stays reproducible
+    pub fn rng_mut(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+
+    /// Handle a message said by a player, the penalities are applied here
+    /// and the returned interactions tell what went wrong
+    pub fn on_say_action(
+        &mut self,
+        player_index: usize,
+        message: String,
+    ) -> anyhow::Result<Vec<WrongPlayerInteraction>> {
         let event = MaoEvent::SayEvent {
             message,
             player_index,
         };
         let res = self.on_event(&event)?;
-        let res = self.propagate_on_event_results_and_execute(player_index, &event, &res)?;
+        // rules not ignoring the event override the out of turn penality
+        let overridden = res
+            .iter()
+            .any(|v| !matches!(v.res_type, MaoEventResultType::Ignored));
+        let mut res = self.propagate_on_event_results_and_execute(player_index, &event, &res)?;
+        if self.config.penalize_out_of_turn_say && player_index != self.player_turn && !overridden {
+            res.push(WrongPlayerInteraction::Disallow(Disallow::new(
+                "Basic Rules".to_string(),
+                "You cannot speak when it is not your turn".to_string(),
+                None,
+            )));
+        }
         for int in &res {
             match int {
                 WrongPlayerInteraction::Disallow(d) => {
@@ -284,9 +427,10 @@ impl MaoCore {
                     ))
                     .into())
                 }
+                WrongPlayerInteraction::GivePenality(give) => self.give_penality(give)?,
             }
         }
-        Ok(())
+        Ok(res)
     }
 
     fn on_action_interaction(
@@ -295,7 +439,7 @@ impl MaoCore {
         interactions: &[MaoInteraction],
     ) -> anyhow::Result<Vec<WrongPlayerInteraction>> {
         let required = &[PlayerAction::SelectPlayer, PlayerAction::DoAction];
-        if !self.correct_player_action(required, interactions) {
+        if self.correct_player_action(required, interactions).is_err() {
             return Ok(vec![]);
         }
 
@@ -327,6 +471,7 @@ impl MaoCore {
                     )
                     .into())
                 }
+                WrongPlayerInteraction::GivePenality(give) => self.give_penality(give)?,
             }
         }
         // THERE
@@ -339,7 +484,7 @@ impl MaoCore {
         interactions: &[MaoInteraction],
     ) -> anyhow::Result<Vec<WrongPlayerInteraction>> {
         let required = vec![PlayerAction::SelectDrawableStack];
-        if !mao.correct_player_action(&required, interactions) {
+        if mao.correct_player_action(&required, interactions).is_err() {
             // TODO
             return Ok(Vec::new());
         }
@@ -434,14 +579,15 @@ impl MaoCore {
             libraries.push(Rule::try_from(rule.as_str())?);
         }
 
-        let mut s = Self::new(
-            libraries,
-            Self::init_stacks(),
-            Vec::new(),
-            Automaton::from_iter(Self::generate_actions()),
-        );
-        s.config = config.to_owned();
-        s.possible_actions = config.get_all_physical_actions().into_iter().collect();
+        let actions = Self::generate_actions();
+        Automaton::validate_paths(&actions)?;
+        let automaton = Automaton::from_iter(actions);
+        automaton.validate()?;
+        let stacks =
+            Self::init_stacks_with(Self::generate_common_draw(), config.playable_stack_count)?;
+        let mut s = Self::new(libraries, stacks, Vec::new(), automaton);
+        s.set_config(config.to_owned());
+        s.turn = config.initial_direction as isize;
         // verify that all rules are valid
         // TODO just not put rules that are not valid in the carbage
         if let Err(e) = s.rules_valid() {
@@ -475,20 +621,72 @@ impl MaoCore {
             .len())
     }
 
-    pub fn init_stacks() -> Vec<Stack> {
-        let mut stacks = vec![Stack::new(
-            Self::generate_common_draw(),
-            false,
-            vec![StackType::Drawable],
-        )];
-        let first_card = stacks.first_mut().unwrap().draw_card().unwrap();
+    /// Returns the [`CardValue`]s of the cards of the player `player_index`
+    pub fn hand_values(&self, player_index: usize) -> Result<Vec<CardValue>, Error> {
+        Ok(self
+            .players
+            .get(player_index)
+            .ok_or(Error::InvalidPlayerIndex {
+                player_index,
+                len: self.players.len(),
+            })?
+            .get_cards()
+            .iter()
+            .map(|card| card.get_value().to_owned())
+            .collect())
+    }
+
+    /// Returns the sum of the points of the cards of the player `player_index`
+    /// (see [`CardValue::point_value`])
+    pub fn hand_point_total(&self, player_index: usize) -> Result<isize, Error> {
+        Ok(self
+            .hand_values(player_index)?
+            .iter()
+            .map(CardValue::point_value)
+            .sum())
+    }
+
+    /// Returns the stacks of a new game made from the common deck, see [`Self::init_stacks_from`]
+    pub fn init_stacks() -> Result<Vec<Stack>, Error> {
+        Self::init_stacks_from(Self::generate_common_draw())
+    }
+
+    /// Returns the stacks of a new game: the drawable stack made of `deck`,
+    /// a playable stack holding the first card of the deck and an empty discardable stack
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`Error::EmptyDeck`] if `deck` is empty
+    pub fn init_stacks_from(deck: Vec<Card>) -> Result<Vec<Stack>, Error> {
+        Self::init_stacks_with(deck, 1)
+    }
+
+    /// Same as [`Self::init_stacks_from`] with `playable_stack_count` playable stacks,
+    /// only the first one holds the first card of the deck
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`Error::EmptyDeck`] if `deck` is empty
+    pub fn init_stacks_with(
+        deck: Vec<Card>,
+        playable_stack_count: usize,
+    ) -> Result<Vec<Stack>, Error> {
+        let mut stacks = vec![Stack::new(deck, false, vec![StackType::Drawable])];
+        let first_card = stacks
+            .first_mut()
+            .unwrap()
+            .draw_card()
+            .ok_or(Error::EmptyDeck)?;
         stacks.push(Stack::new(
             vec![first_card],
             true,
             vec![StackType::Playable],
         ));
+        for _ in 1..playable_stack_count {
+            stacks.push(Stack::new(vec![], true, vec![StackType::Playable]));
+        }
         stacks.push(Stack::new(vec![], true, vec![StackType::Discardable]));
-        stacks
+        Ok(stacks)
     }
 
     pub fn new(
@@ -511,13 +709,55 @@ impl MaoCore {
             config: Config::default(),
             previous_player_turn: None,
             possible_actions: Vec::new(),
+            game_data: Data::new(),
+            rng: StdRng::from_entropy(),
+            rule_fire_counts: HashMap::new(),
+            event_history: Vec::new(),
+            last_draw: None,
+        }
+    }
+
+    /// Creates a [`MaoCore`] without any rule and with the default actions,
+    /// no directory is needed so the game logic can be tested alone
+    pub fn new_for_testing(players: Vec<Player>, stacks: Vec<Stack>) -> Self {
+        Self::new(
+            Vec::new(),
+            stacks,
+            players,
+            Automaton::from_iter(Self::generate_actions()),
+        )
+    }
+
+    /// Returns a prompt describing the selections expected to go on with the interaction in progress,
+    /// `None` is returned if no interaction is in progress or if nothing can follow
+    pub fn current_prompt(&self) -> Option<String> {
+        self.automaton.current_state()?;
+        let actions = self.automaton.next_actions();
+        if actions.is_empty() {
+            return None;
         }
+        Some(format!(
+            "Select a {}.",
+            actions
+                .iter()
+                .map(|action| action.to_string())
+                .collect::<Vec<String>>()
+                .join(" or a ")
+        ))
     }
 
     pub fn on_action(&mut self, interaction: MaoInteraction) -> MaoInteractionResult {
         self.automaton.on_action(interaction)
     }
 
+    /// Applies a [`GivePenality`] to its player, with its own penality function if any
+    pub(crate) fn give_penality(&mut self, give: &GivePenality) -> anyhow::Result<()> {
+        match give.penality {
+            Some(func) => func(self, give.player_index),
+            None => self.on_penality(give.player_index),
+        }
+    }
+
     fn on_penality(&mut self, player_index: usize) -> anyhow::Result<()> {
         let event = MaoEvent::PlayerPenality {
             player_target: player_index,
@@ -529,6 +769,40 @@ impl MaoCore {
             .all(|ev| matches!(ev.res_type, MaoEventResultType::Ignored))
         {
             self.common_penality_to_player(player_index)?;
+            self.on_event(&MaoEvent::PenaltyApplied {
+                player_index,
+                cards_drawn: 1,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Applies the penality of each of `wrong_int` to the player `player_index`
+    /// (or to the targeted player for a [`WrongPlayerInteraction::GivePenality`])
+    fn penalize_wrong_interactions(
+        &mut self,
+        player_index: usize,
+        wrong_int: &[WrongPlayerInteraction],
+    ) -> Result<(), Error> {
+        for int in wrong_int {
+            match int {
+                WrongPlayerInteraction::Disallow(disallow) => {
+                    if let Some(func) = disallow.penality {
+                        func(self, player_index)?;
+                    } else {
+                        self.on_penality(player_index)?;
+                    }
+                }
+                WrongPlayerInteraction::ForgotSomething(f) => {
+                    if let Some(func) = f.penality {
+                        // TODO it is not always the player hand
+                        func(self, player_index)?;
+                    } else {
+                        self.on_penality(player_index)?;
+                    }
+                }
+                WrongPlayerInteraction::GivePenality(give) => self.give_penality(give)?,
+            }
         }
         Ok(())
     }
@@ -537,6 +811,7 @@ impl MaoCore {
         &mut self,
         card_event: CardEvent,
     ) -> Result<Vec<WrongPlayerInteraction>, Error> {
+        card_event.validate(self)?;
         // let turn_ends_wront_int = self.on_turn_ends()?;
         let event = MaoEvent::PlayedCardEvent(card_event.to_owned());
 
@@ -546,27 +821,7 @@ impl MaoCore {
         let mut wrong_int =
             self.propagate_on_event_results_and_execute(card_event.player_index, &event, &res)?;
         if !wrong_int.is_empty() {
-            for int in &wrong_int {
-                match int {
-                    WrongPlayerInteraction::Disallow(disallow) => {
-                        if let Some(func) = disallow.penality {
-                            func(self, card_event.player_index)?;
-                        } else {
-                            self.on_penality(card_event.player_index)?;
-                        }
-                    }
-                    // this should never occur
-                    // TODO REMOVE
-                    WrongPlayerInteraction::ForgotSomething(f) => {
-                        if let Some(func) = f.penality {
-                            // TODO it is not always the player hand
-                            func(self, card_event.player_index)?;
-                        } else {
-                            self.on_penality(card_event.player_index)?;
-                        }
-                    }
-                }
-            }
+            self.penalize_wrong_interactions(card_event.player_index, &wrong_int)?;
             wrong_int.extend(self.on_turn_ends(true)?);
             // wrong_int.extend(turn_ends_wront_int);
             return Ok(wrong_int);
@@ -581,7 +836,14 @@ impl MaoCore {
         // cannot play disallowed
         if !matches!(player_turn_res, PlayerTurnResult::CanPlay) {
             let mut res_wrong_int = self.on_turn_ends(true)?;
-            self.on_penality(card_event.player_index)?;
+            let nb_penalities = match player_turn_res {
+                PlayerTurnResult::WrongTurn => self.config.wrong_turn_penality,
+                PlayerTurnResult::CannotPlaceThisCard { .. } => self.config.wrong_card_penality,
+                _ => 1,
+            };
+            for _ in 0..nb_penalities {
+                self.on_penality(card_event.player_index)?;
+            }
             self.next_player(card_event.player_index, &event, true)?;
             let msg: String = match player_turn_res {
                 PlayerTurnResult::CanPlay => unreachable!("can play"),
@@ -591,7 +853,7 @@ impl MaoCore {
                     ..
                 } => format!(
                     "You cannot play this card {}",
-                    placed_card.to_string_light()
+                    placed_card.to_string_light(false)
                 ),
                 PlayerTurnResult::Other { desc } => desc.to_owned(),
             };
@@ -653,19 +915,17 @@ impl MaoCore {
         for i in (0..last_index).rev() {
             let event = self.player_events.get(i).unwrap();
             if event.can_change_turn() {
+                // everything before belongs to the previous turns
                 range = Some(Range {
                     start: 0,
                     end: i + 1,
                 });
+                break;
             } else {
+                // a given card does not belong to the turn of its giver
                 let event_res = match event {
-                    MaoEvent::PlayedCardEvent(card_event)
-                    | MaoEvent::DiscardCardEvent(card_event)
-                    | MaoEvent::DrawedCardEvent(card_event) => Some(card_event.player_index),
-                    MaoEvent::SayEvent { player_index, .. }
-                    | MaoEvent::PhysicalEvent { player_index, .. } => Some(*player_index),
                     MaoEvent::GiveCardEvent { .. } => None,
-                    _ => None,
+                    _ => event.actor(),
                 };
 
                 // The current action of this player turn can be either related to the turn before him or its own turn
@@ -678,19 +938,29 @@ impl MaoCore {
         }
         // events are in reversed order (newer...last_ones)
         let mut datas: Vec<MaoEvent> = Vec::new();
+        // only the events leaving `player_events` go to the history, the kept ones
+        // will be added once they are removed
+        let mut removed: Vec<MaoEvent> = Vec::new();
         for (index, remove) in indexes.drain(..) {
             if remove {
-                datas.push(self.player_events.remove(index));
+                let event = self.player_events.remove(index);
+                removed.push(event.to_owned());
+                datas.push(event);
             } else {
                 datas.push(self.player_events.get(index).unwrap().to_owned());
             }
         }
         if let Some(range) = range {
-            datas.extend(self.player_events.drain(range).rev());
+            let drained: Vec<MaoEvent> = self.player_events.drain(range).rev().collect();
+            removed.extend(drained.iter().cloned());
+            datas.extend(drained);
         }
         if datas.is_empty() {
             return Ok(Vec::new());
         }
+        if self.config.retain_events {
+            self.event_history.extend(removed.into_iter().rev());
+        }
         let event = MaoEvent::EndPlayerTurn {
             // events: self.player_events.extract_if(|_| false).collect(),
             events: datas,
@@ -715,6 +985,7 @@ impl MaoCore {
                             self.on_penality(self.player_turn)?;
                         }
                     }
+                    WrongPlayerInteraction::GivePenality(give) => self.give_penality(give)?,
                 }
             }
             return Ok(wrong_int);
@@ -828,15 +1099,10 @@ impl MaoCore {
         mao: &mut MaoCore,
         interactions: &[MaoInteraction],
     ) -> anyhow::Result<Vec<WrongPlayerInteraction>> {
-        let expected = vec![PlayerAction::SelectCard, PlayerAction::SelectPlayableStack];
-        if !mao.correct_player_action(&expected, interactions) {
-            // TODO
-            return Err(Error::InvalidMaoInteraction {
-                expected,
-                received: interactions.iter().map(|v| v.action.to_owned()).collect(),
-            }
-            .into());
-        }
+        mao.correct_player_action(
+            &[PlayerAction::SelectCard, PlayerAction::SelectPlayableStack],
+            interactions,
+        )?;
         let card_index = interactions[0]
             .data
             .as_ref()
@@ -884,54 +1150,400 @@ impl MaoCore {
         // mao.playsc
     }
 
-    pub fn player_turn(&self) -> usize {
-        self.player_turn
+    /// Plays the [`Card`] `card_index` of the player `player_index` on the [`Stack`] `stack_index`
+    /// (on a new stack if `None`) without going through the interactions of the [`Automaton`]
+    ///
+    /// # Errors
+    ///
+    /// fails if the player, the card or the stack does not exist
+    pub fn play_card(
+        &mut self,
+        player_index: usize,
+        card_index: usize,
+        stack_index: Option<usize>,
+    ) -> Result<Vec<WrongPlayerInteraction>, Error> {
+        let player = self
+            .players
+            .get(player_index)
+            .ok_or(Error::InvalidPlayerIndex {
+                player_index,
+                len: self.players.len(),
+            })?;
+        let card = player
+            .get_cards()
+            .get(card_index)
+            .ok_or(Error::InvalidCardIndex {
+                card_index,
+                len: player.len(),
+            })?
+            .to_owned();
+        if let Some(stack_index) = stack_index {
+            if stack_index >= self.stacks.len() {
+                return Err(Error::InvalidStackIndex {
+                    stack_index,
+                    len: self.stacks.len(),
+                });
+            }
+        }
+        self.on_play_card(CardEvent {
+            card_index,
+            played_card: card,
+            player_index,
+            stack_index,
+        })
     }
 
-    pub fn player_won(&self) -> Option<(usize, &Player)> {
-        self.players
+    /// Plays the cards `card_indices` of the player `player_index` one after the other on `stack_index`,
+    /// the turn only changes once all the cards have been played
+    ///
+    /// The first given card must be placeable on the stack, the other ones follow it in the given order,
+    /// if it cannot be placed it is played alone with [`Self::play_card`] (penalities included)
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if multiple play is disabled in the [`Config`],
+    /// if an index is invalid or if the cards do not share the same value
+    pub fn play_multiple(
+        &mut self,
+        player_index: usize,
+        card_indices: &[usize],
+        stack_index: Option<usize>,
+    ) -> Result<Vec<WrongPlayerInteraction>, Error> {
+        if !self.config.allow_multiple_play {
+            return Err(Error::DisabledByConfig {
+                feature: String::from("playing multiple cards"),
+            });
+        }
+        let player = self
+            .players
+            .get(player_index)
+            .ok_or(Error::InvalidPlayerIndex {
+                player_index,
+                len: self.players.len(),
+            })?;
+        // the cards are played in the given order, each one only once
+        let mut unique_indices: Vec<usize> = Vec::with_capacity(card_indices.len());
+        for &card_index in card_indices {
+            if !unique_indices.contains(&card_index) {
+                unique_indices.push(card_index);
+            }
+        }
+        let card_indices = unique_indices;
+        let mut cards = Vec::with_capacity(card_indices.len());
+        for &card_index in &card_indices {
+            cards.push(
+                player
+                    .get_cards()
+                    .get(card_index)
+                    .ok_or(Error::InvalidCardIndex {
+                        card_index,
+                        len: player.len(),
+                    })?,
+            );
+        }
+        let value = cards.first().ok_or(Error::GivenSliceEmpty)?.get_value();
+        if cards.iter().any(|card| card.get_value() != value) {
+            return Err(Error::OnMaoInteraction(format!(
+                "The cards {} do not share the same value",
+                cards
+                    .iter()
+                    .map(|card| card.to_string_light(false))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )));
+        }
+
+        let card_events: Vec<CardEvent> = card_indices
             .iter()
-            .enumerate()
-            .find(|(_, player)| player.get_cards().is_empty())
-    }
+            .zip(cards)
+            .map(|(&card_index, card)| CardEvent {
+                card_index,
+                played_card: card.to_owned(),
+                player_index,
+                stack_index,
+            })
+            .collect();
+        for card_event in &card_events {
+            card_event.validate(self)?;
+        }
+        // the first given card is placed on the stack, the other ones share its value
+        // so they can follow it
+        let first = card_events.first().unwrap();
+        let player_turn_res = self.can_play(
+            player_index,
+            &first.played_card,
+            stack_index.and_then(|i| self.stacks.get(i)),
+        );
+        if card_events.len() == 1 || !matches!(player_turn_res, PlayerTurnResult::CanPlay) {
+            // handled like a single card (penalities included)
+            return self.play_card(player_index, first.card_index, stack_index);
+        }
 
-    pub fn players(&self) -> &[Player] {
-        &self.players
-    }
+        let events: Vec<MaoEvent> = card_events
+            .iter()
+            .map(|card_event| MaoEvent::PlayedCardEvent(card_event.to_owned()))
+            .collect();
+        let mut res = Vec::new();
+        for event in &events {
+            res.extend(self.on_event(event)?);
+        }
+        // only the first card is seen as the event ending the previous turn
+        let others = self
+            .player_events
+            .split_off(self.player_events.len() - (events.len() - 1));
 
-    pub fn players_events(&self) -> &[MaoEvent] {
-        &self.player_events
-    }
+        let mut wrong_int = self.propagate_on_event_results_and_execute(
+            player_index,
+            events.first().unwrap(),
+            &res,
+        )?;
+        if !wrong_int.is_empty() {
+            self.penalize_wrong_interactions(player_index, &wrong_int)?;
+            wrong_int.extend(self.on_turn_ends(true)?);
+            return Ok(wrong_int);
+        }
 
-    pub fn players_mut(&mut self) -> &mut Vec<Player> {
-        &mut self.players
+        let wrong_int = self.on_turn_ends(false)?;
+        self.player_events.extend(others);
+        let mut stack_index = stack_index;
+        for card_event in &card_events {
+            match stack_index {
+                Some(stack_index) => self.push_card_into_stack_target(
+                    StackTarget::Stack(stack_index),
+                    card_event.played_card.to_owned(),
+                )?,
+                None => {
+                    // the next cards go on the stack created by the first one
+                    self.new_played_stack(&[card_event.played_card.to_owned()], true);
+                    stack_index = Some(self.stacks.len() - 1);
+                }
+            }
+        }
+        // the highest indexes first so that the remaining ones stay valid
+        let mut removed_indices = card_indices;
+        removed_indices.sort_unstable_by(|a, b| b.cmp(a));
+        for card_index in removed_indices {
+            self.remove_card_from_stack_target(StackTarget::Player(player_index), card_index)?;
+        }
+        let cards: Vec<&Card> = card_events
+            .iter()
+            .map(|card_event| &card_event.played_card)
+            .collect();
+        self.change_turn_after_playing(&cards);
+        Ok(wrong_int)
     }
 
-    pub fn possible_actions(&self) -> &[String] {
-        &self.possible_actions
+    pub fn player_turn(&self) -> usize {
+        self.player_turn
     }
 
-    pub fn set_can_play_on_new_stack(&mut self, can_play_on_new_stack: bool) {
-        self.can_play_on_new_stack = can_play_on_new_stack;
+    /// Returns the index of the player who had the turn before the current one
+    pub fn previous_player(&self) -> Option<usize> {
+        self.previous_player_turn
     }
 
-    pub fn set_dealer(&mut self, dealer: usize) {
-        self.dealer = dealer;
+    /// Returns the player who had the turn before the current one
+    pub fn previous_player_ref(&self) -> Option<&Player> {
+        self.previous_player_turn
+            .and_then(|index| self.players.get(index))
     }
 
-    pub fn stacks(&self) -> &[Stack] {
-        &self.stacks
+    /// Set the player whose turn it is, the current one becomes the previous one
+    ///
+    /// This bypasses every turn change logic (card effects and rules),
+    /// it is meant for scripted scenarios and recovery from the host
+    pub fn set_player_turn(&mut self, index: usize) -> Result<(), Error> {
+        if index >= self.players.len() {
+            return Err(Error::InvalidPlayerIndex {
+                player_index: index,
+                len: self.players.len(),
+            });
+        }
+        self.previous_player_turn = Some(self.player_turn);
+        self.player_turn = index;
+        Ok(())
     }
 
-    pub fn stacks_mut(&mut self) -> &mut Vec<Stack> {
-        &mut self.stacks
+    pub fn player_won(&self) -> Option<(usize, &Player)> {
+        self.players
+            .iter()
+            .enumerate()
+            .find(|(_, player)| player.is_empty())
     }
-}
 
-// miscellious
-impl MaoCore {
-    pub fn get_executed_actions(&self) -> Vec<&NodeState> {
-        self.automaton.get_executed_actions()
+    /// Returns the pseudo of the player who won (see [`Self::player_won`])
+    pub fn winner_pseudo(&self) -> Option<String> {
+        self.player_won()
+            .map(|(_, player)| player.get_pseudo().to_owned())
+    }
+
+    /// Returns the hands of all players as seen by the player `viewer`,
+    /// a `None` viewer (a spectator) only sees the cards visible to other players
+    pub fn hand_summary(&self, viewer: Option<usize>) -> Vec<PlayerHandSummary> {
+        self.players
+            .iter()
+            .enumerate()
+            .map(|(i, player)| {
+                let is_owner = viewer == Some(i);
+                PlayerHandSummary {
+                    pseudo: player.get_pseudo().to_owned(),
+                    card_count: player.len(),
+                    visible_cards: player
+                        .get_cards()
+                        .iter()
+                        .map(|card| {
+                            let visible = if is_owner {
+                                card.owner_can_see_it()
+                            } else {
+                                card.other_can_see_it()
+                            };
+                            visible.then(|| card.to_owned())
+                        })
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the (player index, card index) of the first player holding a [`Card`] with the value and the sign of `card`,
+    /// whatever its rule and its visibility
+    pub fn find_card_holder(&self, card: &Card) -> Option<(usize, usize)> {
+        self.players
+            .iter()
+            .enumerate()
+            .find_map(|(player_index, player)| {
+                player
+                    .get_cards()
+                    .iter()
+                    .position(|c| c.same_card(card))
+                    .map(|card_index| (player_index, card_index))
+            })
+    }
+
+    /// Returns a snapshot of the game as seen by the player `viewer` (or by a spectator if `None`),
+    /// the hidden cards are masked just like in [`Self::hand_summary`]
+    pub fn public_state(&self, viewer: Option<usize>) -> PublicGameState {
+        PublicGameState {
+            hands: self.hand_summary(viewer),
+            stacks: self
+                .stacks
+                .iter()
+                .map(|stack| PublicStackState {
+                    stack_types: stack.get_stack_types().to_vec(),
+                    visible: stack.visible(),
+                    card_count: stack.len(),
+                    top: stack.then(|| stack.top().cloned()).flatten(),
+                })
+                .collect(),
+            player_turn: self.player_turn,
+            turn: self.turn,
+            activated_rules: self
+                .activated_rules
+                .iter()
+                .filter_map(|&i| self.available_rules.get(i))
+                .map(|rule| rule.name().to_owned())
+                .collect(),
+        }
+    }
+
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    /// Returns the number of players of the game
+    pub fn players_count(&self) -> usize {
+        self.players.len()
+    }
+
+    pub fn players_events(&self) -> &[MaoEvent] {
+        &self.player_events
+    }
+
+    pub fn clear_player_events(&mut self) {
+        self.player_events.clear();
+    }
+
+    /// Returns the events of the previous turns of this game, see `retain_events` of [`Config`]
+    pub fn event_history(&self) -> &[MaoEvent] {
+        &self.event_history
+    }
+
+    /// Returns the events of `players_events` which have been done by the player `player_index`
+    pub fn events_for_player(&self, player_index: usize) -> Vec<&MaoEvent> {
+        self.player_events
+            .iter()
+            .filter(|event| {
+                let event_player = match event {
+                    MaoEvent::PlayerPenality { player_target } => Some(*player_target),
+                    MaoEvent::PenaltyApplied { player_index, .. }
+                    | MaoEvent::PlayerJoined { player_index }
+                    | MaoEvent::HandLimitExceeded { player_index } => Some(*player_index),
+                    _ => event.actor(),
+                };
+                event_player == Some(player_index)
+            })
+            .collect()
+    }
+
+    /// Returns the most recently played [`Card`] still recorded with the index of the player who played it
+    pub fn last_played(&self) -> Option<(usize, &Card)> {
+        self.player_events
+            .iter()
+            .rev()
+            .find_map(|event| match event {
+                MaoEvent::PlayedCardEvent(card_event) => {
+                    Some((card_event.player_index, &card_event.played_card))
+                }
+                _ => None,
+            })
+    }
+
+    pub fn players_mut(&mut self) -> &mut Vec<Player> {
+        &mut self.players
+    }
+
+    pub fn possible_actions(&self) -> &[String] {
+        &self.possible_actions
+    }
+
+    /// Replaces the [`Config`] of the game, the possible actions are updated accordingly
+    pub fn set_config(&mut self, config: Config) {
+        self.config = config;
+        self.refresh_possible_actions();
+    }
+
+    pub fn set_can_play_on_new_stack(&mut self, can_play_on_new_stack: bool) {
+        self.can_play_on_new_stack = can_play_on_new_stack;
+    }
+
+    pub fn set_dealer(&mut self, dealer: usize) {
+        self.dealer = dealer;
+    }
+
+    pub fn stacks(&self) -> &[Stack] {
+        &self.stacks
+    }
+
+    pub fn stacks_mut(&mut self) -> &mut Vec<Stack> {
+        &mut self.stacks
+    }
+}
+
+// miscellious
+impl MaoCore {
+    pub fn get_executed_actions(&self) -> Vec<&NodeState> {
+        self.automaton.get_executed_actions()
+    }
+
+    /// Returns the interactions (with their data) of the action in progress
+    /// or of the last completed one, see [`Automaton::executed_interactions`]
+    pub fn executed_interactions(&self) -> Vec<MaoInteraction> {
+        self.automaton.executed_interactions()
+    }
+
+    /// Cancels the action in progress, see [`Automaton::abort`]
+    pub fn abort_current_interaction(&mut self) -> Vec<MaoInteraction> {
+        self.automaton.abort()
     }
     /// This function will draw `nb` cards from all avaible drawable stacks,
     /// it returns a [`Vec`] with exactly `nb` cards
@@ -947,11 +1559,12 @@ impl MaoCore {
         while nb != 0 {
             let drawable_stacks = self.get_drawable_stacks_mut();
             // all stack are empty
-            if !drawable_stacks
-                .iter()
-                .any(|(_, stack)| !stack.get_cards().is_empty())
-            {
+            if !drawable_stacks.iter().any(|(_, stack)| !stack.is_empty()) {
                 if empty_first {
+                    // give back the already drawn cards
+                    if let Some((_, stack)) = drawable_stacks.into_iter().next() {
+                        stack.get_cards_mut().append(&mut cards);
+                    }
                     return Err(Error::NotEnoughCards);
                 } else {
                     self.refill_drawable_stacks(None, true)?;
@@ -960,8 +1573,8 @@ impl MaoCore {
                 }
             }
             for (_, stack) in drawable_stacks {
-                if stack.get_cards().len() >= nb {
-                    let index = stack.get_cards().len().saturating_sub(nb);
+                if stack.len() >= nb {
+                    let index = stack.len().saturating_sub(nb);
                     cards.extend_from_slice(
                         &(*stack)
                             .get_cards_mut()
@@ -972,16 +1585,193 @@ impl MaoCore {
                     nb = 0;
                     break;
                 } else {
-                    nb -= stack.get_cards().len();
+                    nb -= stack.len();
                     cards.append((*stack).get_cards_mut());
                 }
             }
 
             self.refill_drawable_stacks(None, false)?;
         }
+        self.refill_if_below_threshold()?;
         Ok(cards)
     }
 
+    /// Computes again the possible physical actions from the current cards effects
+    pub fn refresh_possible_actions(&mut self) {
+        let mut actions: Vec<String> = self.config.get_all_physical_actions().into_iter().collect();
+        actions.sort();
+        self.possible_actions = actions;
+    }
+
+    /// Draws the first [`Card`] matching `pred` from the [`Stack`] `stack_index` into the hand of the player `player_index`
+    /// and returns it, the rules are not called
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if
+    /// + `player_index` or `stack_index` is invalid
+    /// + there is no card matching `pred` inside the stack
+    pub fn draw_specific_card(
+        &mut self,
+        player_index: usize,
+        stack_index: usize,
+        pred: impl Fn(&Card) -> bool,
+    ) -> Result<Card, Error> {
+        if player_index >= self.players.len() {
+            return Err(Error::InvalidPlayerIndex {
+                player_index,
+                len: self.players.len(),
+            });
+        }
+        let card = self
+            .get_stack_target(StackTarget::Stack(stack_index))?
+            .remove_first_matching(&pred)
+            .ok_or(Error::NotEnoughCards)?;
+        self.add_cards_to_hand(player_index, [card.to_owned()])?;
+        Ok(card)
+    }
+
+    /// Discards the [`Card`] `card_index` of the player `player_index` into the discardable [`Stack`] `stack_index`
+    /// (or the default one if `None`) and ends the turn of the player
+    ///
+    /// The rules are called with [`MaoEvent::DiscardCardEvent`], the card is not discarded if some disallow it
+    ///
+    /// # Errors
+    ///
+    /// fails if the player, the card or the stack does not exist or if the stack is not discardable
+    pub fn discard_from_hand(
+        &mut self,
+        player_index: usize,
+        card_index: usize,
+        stack_index: Option<usize>,
+    ) -> Result<Vec<WrongPlayerInteraction>, Error> {
+        let player = self
+            .players
+            .get(player_index)
+            .ok_or(Error::InvalidPlayerIndex {
+                player_index,
+                len: self.players.len(),
+            })?;
+        let card = player
+            .get_cards()
+            .get(card_index)
+            .ok_or(Error::InvalidCardIndex {
+                card_index,
+                len: player.len(),
+            })?
+            .to_owned();
+        let stack_index = match stack_index {
+            Some(i) => {
+                let stack = self.stacks.get(i).ok_or(Error::InvalidStackIndex {
+                    stack_index: i,
+                    len: self.stacks.len(),
+                })?;
+                if !stack.get_stack_types().contains(&StackType::Discardable) {
+                    return Err(Error::NoStackAvailable {
+                        stacks: vec![StackType::Discardable],
+                    });
+                }
+                i
+            }
+            None => self
+                .default_discard_target()
+                .ok_or(Error::NoStackAvailable {
+                    stacks: vec![StackType::Discardable],
+                })?,
+        };
+        let event = MaoEvent::DiscardCardEvent(CardEvent {
+            played_card: card.to_owned(),
+            card_index,
+            player_index,
+            stack_index: Some(stack_index),
+        });
+        let res = self.on_event(&event)?;
+        let mut wrong_int =
+            self.propagate_on_event_results_and_execute(player_index, &event, &res)?;
+        if !wrong_int.is_empty() {
+            self.penalize_wrong_interactions(player_index, &wrong_int)?;
+            // the refused discard must not be seen as the end of a turn later on
+            if player_index == self.player_turn {
+                wrong_int.extend(self.on_turn_ends(true)?);
+            } else {
+                self.player_events.pop();
+            }
+            return Ok(wrong_int);
+        }
+
+        let turn_ends_wrong_int = if player_index == self.player_turn {
+            self.on_turn_ends(false)?
+        } else {
+            vec![]
+        };
+        let card =
+            self.remove_card_from_stack_target(StackTarget::Player(player_index), card_index)?;
+        self.push_card_into_stack_target(StackTarget::Stack(stack_index), card)?;
+        self.next_player(player_index, &event, false)?;
+        Ok(turn_ends_wrong_int)
+    }
+
+    /// Plays out of turn the [`Card`] `card_index` of the player `player_index`
+    /// which has to be identical (value and sign) to the top card of a playable stack
+    ///
+    /// The player takes the turn, so the turn goes on from it once the card is played,
+    /// if no top card is identical the player gets a penality
+    ///
+    /// # Errors
+    ///
+    /// fails if `allow_jump_in` is disabled in the [`Config`] or if the player or the card does not exist
+    pub fn jump_in(
+        &mut self,
+        player_index: usize,
+        card_index: usize,
+    ) -> Result<Vec<WrongPlayerInteraction>, Error> {
+        if !self.config.allow_jump_in {
+            return Err(Error::DisabledByConfig {
+                feature: String::from("jump in"),
+            });
+        }
+        let player = self
+            .players
+            .get(player_index)
+            .ok_or(Error::InvalidPlayerIndex {
+                player_index,
+                len: self.players.len(),
+            })?;
+        let card = player
+            .get_cards()
+            .get(card_index)
+            .ok_or(Error::InvalidCardIndex {
+                card_index,
+                len: player.len(),
+            })?
+            .to_owned();
+        let stack_index = self
+            .top_playable_cards()
+            .into_iter()
+            .find(|(_, top)| top.is_some_and(|top| top.same_card(&card)))
+            .map(|(i, _)| i);
+        let Some(stack_index) = stack_index else {
+            self.on_penality(player_index)?;
+            return Ok(vec![WrongPlayerInteraction::Disallow(Disallow::new(
+                "Basic Rules".to_string(),
+                format!(
+                    "You cannot jump in with this card {}",
+                    card.to_string_light(false)
+                ),
+                None,
+            ))]);
+        };
+        // the previous player stays the one who played the last card
+        // so that the end of its turn is checked against it
+        self.player_turn = player_index;
+        self.on_play_card(CardEvent {
+            card_index,
+            played_card: card,
+            player_index,
+            stack_index: Some(stack_index),
+        })
+    }
+
     /// Enable a rule according to its name, searching from the available rules
     ///
     /// # Errors
@@ -999,6 +1789,30 @@ impl MaoCore {
         Ok(())
     }
 
+    /// Activate all the rules of `names`, the rules which can be activated are
+    /// activated even if some others failed
+    ///
+    /// Returns the errors of all the rules which could not be activated
+    pub fn activate_rules(&mut self, names: &[&str]) -> Result<(), Vec<Error>> {
+        let errors: Vec<Error> = names
+            .iter()
+            .filter_map(|name| {
+                let rule_name = "lib".to_owned() + name;
+                match self.get_avalaible_rule_by_name(&rule_name) {
+                    Some((index, _)) => self.activate_rule_by_index(index).err(),
+                    None => Some(Error::RuleNotFound {
+                        desc: DmDescription(format!("The rule {} has not been found", rule_name)),
+                    }),
+                }
+            })
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
     pub fn activate_rule_by_index(&mut self, index: usize) -> Result<(), Error> {
         // the index des not correspond to an available rule
         match self.available_rules.get(index) {
@@ -1010,6 +1824,22 @@ impl MaoCore {
                     });
                 }
 
+                // the activated rules can refuse the new one
+                let rule_name = rule.name().to_owned();
+                let res = self.on_event(&MaoEvent::RuleActivated {
+                    rule_name: rule_name.to_owned(),
+                })?;
+                if let Some(disallow) = res.iter().find_map(|r| match &r.res_type {
+                    MaoEventResultType::Disallow(disallow) => Some(disallow),
+                    _ => None,
+                }) {
+                    return Err(Error::RuleVetoed {
+                        rule_name,
+                        reason: disallow.to_string(),
+                    });
+                }
+
+                let rule = self.available_rules.get(index).unwrap();
                 if let Some(actions) = rule.get_actions() {
                     self.automaton.extend(actions);
                 }
@@ -1023,6 +1853,35 @@ impl MaoCore {
         }
     }
 
+    /// Returns the [`RuleInfo`] of the available rule at `index`
+    pub fn rule_info(&self, index: usize) -> Result<RuleInfo, Error> {
+        let rule = self
+            .available_rules
+            .get(index)
+            .ok_or(Error::InvalidRuleIndex {
+                rule_index: index,
+                len: self.available_rules.len(),
+            })?;
+        Ok(RuleInfo {
+            name: rule.name().to_owned(),
+            author: rule.author().map(String::from),
+            description: rule.description().map(String::from),
+            active: self.activated_rules.contains(&index),
+        })
+    }
+
+    /// Returns how many times each rule did not ignore an event, keyed by the name of the rule
+    pub fn rule_fire_counts(&self) -> HashMap<&str, usize> {
+        self.rule_fire_counts
+            .iter()
+            .filter_map(|(&index, &count)| {
+                self.available_rules
+                    .get(index)
+                    .map(|rule| (rule.name(), count))
+            })
+            .collect()
+    }
+
     pub fn deactivate_rule_by_index(&mut self, index: usize) -> Result<(), Error> {
         // TODO remove actions that the rule added
         // the index des not correspond to an available rule
@@ -1040,13 +1899,17 @@ impl MaoCore {
             });
         }
         self.activated_rules.retain(|&id| id != index);
+        self.on_event(&MaoEvent::RuleDeactivated {
+            rule_name: self.available_rules.get(index).unwrap().name().to_owned(),
+        })?;
         Ok(())
     }
 
     /// Checks if a player can play its card according to the initial Mao rules
     ///
     /// This function will firstly check if it is the player turn and therefore check the values and the color of the concerned card
-    fn can_play(
+    /// A card tagged with a rule can only be played while this rule is activated
+    pub fn can_play(
         &self,
         player_index: usize,
         card: &Card,
@@ -1055,6 +1918,35 @@ impl MaoCore {
         if player_index != self.player_turn {
             return PlayerTurnResult::WrongTurn;
         }
+        self.can_place(card, stack)
+    }
+
+    /// Checks if `card` can be placed on `stack` according to the initial Mao rules, whoever's turn it is
+    ///
+    /// A [`CardValue::PlusInfinity`] card can be placed on any card, a [`CardValue::MinusInfinity`] card
+    /// too if `infinity_wild` is enabled in the [`Config`] and never otherwise
+    pub fn can_place(&self, card: &Card, stack: Option<&Stack>) -> PlayerTurnResult {
+        if let Some(rule_name) = card.get_rule() {
+            if !self.is_rule_activated(rule_name) {
+                return PlayerTurnResult::Other {
+                    desc: format!("The rule {} of this card is not activated", rule_name),
+                };
+            }
+        }
+        match card.get_value() {
+            CardValue::PlusInfinity => return PlayerTurnResult::CanPlay,
+            CardValue::MinusInfinity if self.config.infinity_wild => {
+                return PlayerTurnResult::CanPlay
+            }
+            CardValue::MinusInfinity => {
+                return PlayerTurnResult::Other {
+                    desc: String::from(
+                        "The minus infinity cards are disabled by the configuration",
+                    ),
+                }
+            }
+            CardValue::Number(_) => (),
+        }
         if let Some(stack) = stack {
             if let Some(top_card) = stack.top() {
                 if card.get_value() != top_card.get_value()
@@ -1071,6 +1963,134 @@ impl MaoCore {
         PlayerTurnResult::CanPlay
     }
 
+    /// Returns all the (card index, stack index) of the cards of the player `player_index`
+    /// which can be placed on a playable stack, whoever's turn it is
+    pub fn legal_moves(&self, player_index: usize) -> Vec<(usize, usize)> {
+        let Some(player) = self.players.get(player_index) else {
+            return vec![];
+        };
+        let stacks = self.get_playable_stacks();
+        player
+            .get_cards()
+            .iter()
+            .enumerate()
+            .flat_map(|(card_index, card)| {
+                stacks
+                    .iter()
+                    .filter(|(_, stack)| {
+                        matches!(self.can_place(card, Some(stack)), PlayerTurnResult::CanPlay)
+                    })
+                    .map(move |(stack_index, _)| (card_index, *stack_index))
+            })
+            .collect()
+    }
+
+    /// Returns the indexes of the cards of the player `player_index` which can be played
+    /// on at least one playable stack, nothing is returned if it is not its turn
+    pub fn playable_card_indices(&self, player_index: usize) -> Vec<usize> {
+        let Some(player) = self.players.get(player_index) else {
+            return vec![];
+        };
+        let stacks = self.get_playable_stacks();
+        player
+            .get_cards()
+            .iter()
+            .enumerate()
+            .filter(|(_, card)| {
+                stacks.iter().any(|(_, stack)| {
+                    matches!(
+                        self.can_play(player_index, card, Some(stack)),
+                        PlayerTurnResult::CanPlay
+                    )
+                })
+            })
+            .map(|(card_index, _)| card_index)
+            .collect()
+    }
+
+    /// The player `player_index` passes its turn, the end of the turn is checked
+    /// like for any other action that changes the turn
+    ///
+    /// Returns the wrong interactions of the ended turn
+    ///
+    /// # Errors
+    ///
+    /// fails if `allow_pass` is disabled in the [`Config`], if the player does not exist,
+    /// if it is not its turn or if a rule does not ignore [`MaoEvent::TurnPassed`]
+    pub fn pass_turn(&mut self, player_index: usize) -> Result<Vec<WrongPlayerInteraction>, Error> {
+        if !self.config.allow_pass {
+            return Err(Error::DisabledByConfig {
+                feature: String::from("passing its turn"),
+            });
+        }
+        if player_index >= self.players.len() {
+            return Err(Error::InvalidPlayerIndex {
+                player_index,
+                len: self.players.len(),
+            });
+        }
+        if player_index != self.player_turn {
+            return Err(Error::OnMaoInteraction(format!(
+                "The player {} cannot pass the turn of the player {}",
+                player_index, self.player_turn
+            )));
+        }
+        let event = MaoEvent::TurnPassed { player_index };
+        if self
+            .on_event(&event)?
+            .iter()
+            .any(|r| !matches!(r.res_type, MaoEventResultType::Ignored))
+        {
+            self.player_events.pop();
+            return Err(Error::OnMaoInteraction(format!(
+                "The player {} is not allowed to pass its turn",
+                player_index
+            )));
+        }
+        // the end of the turn is checked against the player who played the last card
+        // before the passer becomes the previous player
+        let wrong_int = self.on_turn_ends(false)?;
+        self.previous_player_turn = Some(player_index);
+        self.update_turn(PlayerTurnChange::default());
+        Ok(wrong_int)
+    }
+
+    /// Makes the player `player_index` draw until one of its cards can be placed on a playable stack
+    /// (see [`Self::legal_moves`]), the rules are not called
+    ///
+    /// It stops when there is no card left to draw, or when as many cards as there were
+    /// inside all the stacks have been drawn since no card would ever match
+    ///
+    /// Returns the number of drawn cards
+    pub fn draw_until_playable(&mut self, player_index: usize) -> Result<usize, Error> {
+        if player_index >= self.players.len() {
+            return Err(Error::InvalidPlayerIndex {
+                player_index,
+                len: self.players.len(),
+            });
+        }
+        let max_draws: usize = self.stacks.iter().map(|stack| stack.len()).sum();
+        let mut nb_drawn = 0;
+        while nb_drawn < max_draws && self.legal_moves(player_index).is_empty() {
+            match self.draw_multiple_cards_unchosen(1) {
+                Ok(cards) => self.add_cards_to_hand(player_index, cards)?,
+                Err(Error::NotEnoughCards) => break,
+                Err(e) => return Err(e),
+            }
+            nb_drawn += 1;
+        }
+        Ok(nb_drawn)
+    }
+
+    /// Returns true if an activated [`Rule`] has `rule_name` as name
+    pub fn is_rule_activated(&self, rule_name: &str) -> bool {
+        self.activated_rules.iter().any(|&i| {
+            self.available_rules
+                .get(i)
+                .is_some_and(|rule| rule.name() == rule_name)
+        })
+    }
+
     /// Returns the [`Rule`] which as to be activated according to `rule_name`
     #[allow(dead_code)]
     fn get_activated_rule_by_name(&self, rule_name: &str) -> Option<(usize, &Rule)> {
@@ -1078,9 +2098,24 @@ impl MaoCore {
             .and_then(|(i, rule)| self.activated_rules.get(i).and(Some((i, rule))))
     }
 
-    /// Returns the [`Rule`] from all rules according to `rule_name`
-    fn get_avalaible_rule_by_name(&self, rule_name: &str) -> Option<(usize, &Rule)> {
-        MaoCore::get_rule_by_light_filename(&self.available_rules, rule_name)
+    /// Returns the [`Rule`] from all rules according to `rule_name`
+    fn get_avalaible_rule_by_name(&self, rule_name: &str) -> Option<(usize, &Rule)> {
+        MaoCore::get_rule_by_light_filename(&self.available_rules, rule_name)
+    }
+
+    /// Returns the index of the first discardable stack, where discarded cards go by default
+    pub fn default_discard_target(&self) -> Option<usize> {
+        self.get_discardable_stacks().first().map(|(i, _)| *i)
+    }
+
+    /// Returns a [`Vec`] of a reference to a discardable stack and its index
+    pub fn get_discardable_stacks(&self) -> Vec<(usize, &Stack)> {
+        self.get_specific_stacks(&[StackType::Discardable])
+    }
+
+    /// Returns a [`Vec`] of a mutable reference to a discardable stack and its index
+    pub fn get_discardable_stacks_mut(&mut self) -> Vec<(usize, &mut Stack)> {
+        self.get_specific_stacks_mut(&[StackType::Discardable])
     }
 
     /// Returns a [`Vec`] of a reference to a drawable stack and its index
@@ -1093,6 +2128,52 @@ impl MaoCore {
         self.get_specific_stacks_mut(&[StackType::Drawable])
     }
 
+    /// Returns the number of [`Card`]s of each value remaining in all the drawable stacks
+    pub fn drawable_composition(&self) -> HashMap<CardValue, usize> {
+        let mut composition = HashMap::new();
+        for (_, stack) in self.get_drawable_stacks() {
+            for card in stack.get_cards() {
+                *composition.entry(card.get_value().to_owned()).or_default() += 1;
+            }
+        }
+        composition
+    }
+
+    /// Returns the number of [`Card`]s of each suit remaining in all the drawable stacks
+    pub fn drawable_suits(&self) -> HashMap<CardType, usize> {
+        let mut suits = HashMap::new();
+        for (_, stack) in self.get_drawable_stacks() {
+            for card in stack.get_cards() {
+                *suits.entry(card.get_sign().to_owned()).or_default() += 1;
+            }
+        }
+        suits
+    }
+
+    /// Returns the [`Card`] the next draw from `stack_index` would yield without removing it
+    ///
+    /// `None` resolves to the first non-empty drawable stack,
+    /// `Ok(None)` is returned if there is no card to draw
+    pub fn peek_draw(&self, stack_index: Option<usize>) -> Result<Option<&Card>, Error> {
+        match stack_index {
+            Some(index) => {
+                let stack = self.stacks.get(index).ok_or(Error::InvalidStackIndex {
+                    stack_index: index,
+                    len: self.stacks.len(),
+                })?;
+                if !stack.get_stack_types().contains(&StackType::Drawable) {
+                    return Err(Error::NoStackAvailable {
+                        stacks: vec![StackType::Drawable],
+                    });
+                }
+                Ok(stack.top())
+            }
+            None => Ok(self
+                .get_none_empty_drawable_stack()
+                .and_then(|(_, stack)| stack.top())),
+        }
+    }
+
     /// Returns a [`Vec`] of a reference to a playable stack and its index
     pub fn get_playable_stacks(&self) -> Vec<(usize, &Stack)> {
         self.get_specific_stacks(&[StackType::Playable])
@@ -1103,6 +2184,46 @@ impl MaoCore {
         self.get_specific_stacks_mut(&[StackType::Playable])
     }
 
+    /// Returns the available rules whose name or light filename contains `query`
+    /// (case insensitive), the best matches first
+    pub fn find_rule_fuzzy(&self, query: &str) -> Vec<(usize, &Rule)> {
+        let mut found: Vec<(usize, usize, &Rule)> = self
+            .available_rules
+            .iter()
+            .enumerate()
+            .filter_map(|(i, rule)| {
+                let score = [
+                    Self::fuzzy_match_score(rule.name(), query),
+                    Self::fuzzy_match_score(rule.light_filename(), query),
+                ]
+                .into_iter()
+                .flatten()
+                .min()?;
+                Some((score, i, rule))
+            })
+            .collect();
+        found.sort_by_key(|&(score, i, _)| (score, i));
+        found.into_iter().map(|(_, i, rule)| (i, rule)).collect()
+    }
+
+    /// Returns how well `candidate` matches `query` (case insensitive), lower is better
+    ///
+    /// 0 for an exact match, 1 for a prefix, 2 + the extra length for a substring,
+    /// `None` if `query` is not contained in `candidate`
+    pub(crate) fn fuzzy_match_score(candidate: &str, query: &str) -> Option<usize> {
+        let candidate = candidate.to_lowercase();
+        let query = query.to_lowercase();
+        if candidate == query {
+            Some(0)
+        } else if candidate.starts_with(&query) {
+            Some(1)
+        } else if candidate.contains(&query) {
+            Some(2 + candidate.len() - query.len())
+        } else {
+            None
+        }
+    }
+
     /// Returns a [`Rule`] according to `rule_name` with its index
     /// if the rule is not present None is returned
     fn get_rule_by_light_filename<'t>(
@@ -1133,6 +2254,24 @@ impl MaoCore {
             .collect()
     }
 
+    /// Returns the only stack of type `ty` with its index
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`Error::NoStackAvailable`] if there is no stack of this type
+    /// and [`Error::AmbiguousStack`] if there are several
+    pub fn single_stack_of(&self, ty: StackType) -> Result<(usize, &Stack), Error> {
+        let mut stacks = self.get_specific_stacks(&[ty.to_owned()]);
+        match stacks.len() {
+            0 => Err(Error::NoStackAvailable { stacks: vec![ty] }),
+            1 => Ok(stacks.pop().unwrap()),
+            count => Err(Error::AmbiguousStack {
+                stack_type: ty,
+                count,
+            }),
+        }
+    }
+
     /// Returns the mutable stacks which contain the given `stack_types` with their index
     pub fn get_specific_stacks_mut(
         &mut self,
@@ -1179,6 +2318,20 @@ impl MaoCore {
         Ok(s)
     }
 
+    /// Replaces the [`Card`] `card_index` of `target` by `new` in place and returns the old one
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `target` or `card_index` is not valid
+    pub fn replace_card(
+        &mut self,
+        target: StackTarget,
+        card_index: usize,
+        new: Card,
+    ) -> Result<Card, Error> {
+        self.get_stack_target(target)?.replace_card(card_index, new)
+    }
+
     /// Return the first [`Card`] of the [`Stack`] according to `stack_index`
     ///
     /// # Errors
@@ -1194,6 +2347,34 @@ impl MaoCore {
             .map(|stack| stack.top())
     }
 
+    /// Turns the [`Stack`] `stack_index` face up or down and notifies the rules
+    /// with [`MaoEvent::StackVisibilityChanged`]
+    pub fn set_stack_visible(&mut self, stack_index: usize, visible: bool) -> Result<(), Error> {
+        let len = self.stacks.len();
+        self.stacks
+            .get_mut(stack_index)
+            .ok_or(Error::InvalidStackIndex { stack_index, len })?
+            .set_visible(visible);
+        self.on_event(&MaoEvent::StackVisibilityChanged {
+            stack_index,
+            visible,
+        })?;
+        Ok(())
+    }
+
+    /// Returns the top [`Card`] of the first discardable [`Stack`], `None` if it is empty or if there is none
+    pub fn top_of_discard(&self) -> Option<&Card> {
+        self.stacks.get(self.default_discard_target()?)?.top()
+    }
+
+    /// Returns the top [`Card`] of each playable [`Stack`] with the index of the stack
+    pub fn top_playable_cards(&self) -> Vec<(usize, Option<&Card>)> {
+        self.get_playable_stacks()
+            .into_iter()
+            .map(|(i, stack)| (i, stack.top()))
+            .collect()
+    }
+
     /// This function allows you to give a card to a player
     /// if stack_index is given as None, the player will have to choice if there are more than one drawable stack
     ///
@@ -1318,6 +2499,66 @@ impl MaoCore {
         ))
     }
 
+    /// Add a new player with `nb_card` cards from the drawable stacks
+    /// and notify the rules with [`MaoEvent::PlayerJoined`]
+    ///
+    /// Returns the index of the new player
+    pub fn add_player(&mut self, pseudo: String, nb_card: usize) -> Result<usize, Error> {
+        let player = self.init_player(pseudo, nb_card)?;
+        self.players.push(player);
+        let player_index = self.players.len() - 1;
+        self.on_event(&MaoEvent::PlayerJoined { player_index })?;
+        Ok(player_index)
+    }
+
+    /// Remove the player `index`, its cards are moved to `return_cards_to`
+    ///
+    /// The players after it are shifted, `player_turn`, `previous_player_turn` and `dealer`
+    /// are updated accordingly, if it was the turn of the removed player
+    /// it is now the turn of the one following it in the current direction
+    ///
+    /// Returns the removed player (without its cards)
+    pub fn remove_player(
+        &mut self,
+        index: usize,
+        return_cards_to: StackTarget,
+    ) -> Result<Player, Error> {
+        let len = self.players.len();
+        if index >= len || return_cards_to == StackTarget::Player(index) {
+            return Err(Error::InvalidPlayerIndex {
+                player_index: index,
+                len,
+            });
+        }
+        // checking the target before moving anything
+        self.get_stack_target(return_cards_to.to_owned())?;
+        let cards = std::mem::take(self.players[index].get_cards_mut());
+        self.get_stack_target(return_cards_to)?
+            .get_cards_mut()
+            .extend(cards);
+        let player = self.players.remove(index);
+
+        let len = self.players.len();
+        let turn = self.turn;
+        let shift = |i: usize| -> usize {
+            if len == 0 {
+                0
+            } else if i > index {
+                i - 1
+            } else if i == index && turn < 0 {
+                (index as isize - 1).rem_euclid(len as isize) as usize
+            } else if i == index {
+                index % len
+            } else {
+                i
+            }
+        };
+        self.player_turn = shift(self.player_turn);
+        self.dealer = shift(self.dealer);
+        self.previous_player_turn = self.previous_player_turn.filter(|&i| i != index).map(shift);
+        Ok(player)
+    }
+
     pub fn init_players(&mut self, pseudos: &[String], nb_card: usize) -> Result<(), Error> {
         let mut players = Vec::with_capacity(pseudos.len());
         for pseudo in pseudos {
@@ -1348,19 +2589,64 @@ impl MaoCore {
         Ok(())
     }
 
+    /// Draws for each player until its hand holds `target` [`Card`]s,
+    /// the hands already holding enough cards are left untouched
+    pub fn deal_up_to(&mut self, target: usize) -> Result<(), Error> {
+        for i in 0..self.players.len() {
+            let missing = target.saturating_sub(self.players[i].len());
+            if missing == 0 {
+                continue;
+            }
+            let cards = self.draw_multiple_cards_unchosen(missing)?;
+            self.add_cards_to_hand(i, cards)?;
+        }
+        Ok(())
+    }
+
     pub fn init_new_game(&mut self, nb_card: usize) -> Result<(), Error> {
         // TODO set dealer
         for player in self.players.iter_mut() {
             player.get_cards_mut().clear();
         }
-        self.stacks = Self::init_stacks();
+        self.stacks = Self::init_stacks_with(
+            Self::generate_common_draw(),
+            self.config.playable_stack_count,
+        )?;
         self.player_events.clear();
         self.automaton.reset();
+        self.game_data.clear();
+        self.rule_fire_counts.clear();
+        self.event_history.clear();
+        self.last_draw = None;
+        self.turn = self.config.initial_direction as isize;
+        if !self.players.is_empty() {
+            self.player_turn = if self.config.dealer_plays_first {
+                self.dealer
+            } else {
+                (self.dealer as isize + self.turn).rem_euclid(self.players.len() as isize) as usize
+            };
+        }
+        self.previous_player_turn = None;
 
         self.init_all_players(nb_card)?;
         Ok(())
     }
 
+    /// Starts a new game dealing `initial_hand_size` cards (see [`Config`]) to each player
+    pub fn start_game(&mut self) -> Result<(), Error> {
+        self.init_new_game(self.config.initial_hand_size)
+    }
+
+    /// Adds the points of the cards left in each player's hand to its score (see [`CardValue::point_value`])
+    /// and starts a new round
+    pub fn reset_round(&mut self) -> Result<(), Error> {
+        for player_index in 0..self.players.len() {
+            let points = self.hand_point_total(player_index)?;
+            self.players[player_index].add_score(points);
+        }
+        self.start_game()
+    }
+
     /// Add a new played stack filled with the given `cards`
     pub fn new_played_stack(&mut self, cards: &[Card], visible: bool) {
         self.stacks.push(Stack::new(
@@ -1380,7 +2666,53 @@ impl MaoCore {
         vec![]
     }
 
+    /// Returns the effects that `card` would have if the current player played it, without playing it
+    pub fn preview_card_effects(&self, card: &Card) -> CardEffectPreview {
+        let mut turn_changes = Vec::new();
+        let mut player_actions = Vec::new();
+        for effect in self.get_card_effects(card) {
+            match effect {
+                SingleCardEffect::PlayerTurnChange(change) => turn_changes.push(change.to_owned()),
+                SingleCardEffect::CardPlayerAction(action) => {
+                    player_actions.push(action.to_owned())
+                }
+            }
+        }
+        let (next_player, turn) = if turn_changes.is_empty() {
+            self.compute_turn(&PlayerTurnChange::default())
+        } else {
+            turn_changes
+                .iter()
+                .fold((self.player_turn, self.turn), |state, change| {
+                    self.compute_turn_from(state, change)
+                })
+        };
+        CardEffectPreview {
+            turn_changes,
+            player_actions,
+            reverses_direction: turn != self.turn,
+            next_player,
+        }
+    }
+
+    /// Returns the names of the physical actions required when `card` is played,
+    /// contrary to [`Self::possible_actions`] only the effects of this [`Card`] are considered
+    pub fn required_physical_actions(&self, card: &Card) -> Vec<String> {
+        self.get_card_effects(card)
+            .into_iter()
+            .filter_map(|effect| match effect {
+                SingleCardEffect::CardPlayerAction(CardPlayerAction::Physical(name)) => {
+                    Some(name.to_owned())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
     /// Returns all the [`CardEffects`] that a [`Card`] has on
+    ///
+    /// The effects matching only the value come first, then the ones matching only the type
+    /// and finally the ones matching both, each group keeping its insertion order
     fn get_card_effects(&self, card: &Card) -> Vec<&SingleCardEffect> {
         let mut effects = vec![];
         // Searching effects with only its value
@@ -1400,6 +2732,26 @@ impl MaoCore {
         effects
     }
 
+    /// Applies the [`PlayerTurnChange`]s of the played `cards`, or the default one if there is none
+    fn change_turn_after_playing(&mut self, cards: &[&Card]) {
+        self.previous_player_turn = Some(self.player_turn);
+        let changes: Vec<PlayerTurnChange> = cards
+            .iter()
+            .flat_map(|card| self.get_card_effects(card))
+            .filter_map(|card_effect| match card_effect {
+                SingleCardEffect::PlayerTurnChange(change) => Some(change.to_owned()),
+                SingleCardEffect::CardPlayerAction(_) => None,
+            })
+            .collect();
+        if changes.is_empty() {
+            self.update_turn(PlayerTurnChange::default());
+        } else {
+            for change in changes {
+                self.update_turn(change);
+            }
+        }
+    }
+
     /// Handle turn change when a [`MaoEvent`] occurs, player index is used to check if it is the player turn
     pub fn next_player(
         &mut self,
@@ -1416,28 +2768,17 @@ impl MaoCore {
                         self.update_turn(PlayerTurnChange::default());
                         return Ok(());
                     }
+                    self.change_turn_after_playing(&[&card_event.played_card]);
+                    return Ok(());
+                }
+            }
+            MaoEvent::DiscardCardEvent(_) => {
+                if player_index == self.player_turn {
                     self.previous_player_turn = Some(self.player_turn);
-                    let changes: Vec<&PlayerTurnChange> = self
-                        .get_card_effects(&card_event.played_card)
-                        .iter()
-                        .filter_map(|card_effect| match card_effect {
-                            SingleCardEffect::PlayerTurnChange(change) => Some(change),
-                            SingleCardEffect::CardPlayerAction(_) => None,
-                        })
-                        .collect();
-                    if changes.is_empty() {
-                        self.update_turn(PlayerTurnChange::default());
-                    } else {
-                        let changes: Vec<PlayerTurnChange> =
-                            changes.iter().map(|&v| v.to_owned()).collect();
-                        for change in changes {
-                            self.update_turn(change);
-                        }
-                    }
+                    self.update_turn(PlayerTurnChange::default());
                     return Ok(());
                 }
             }
-            MaoEvent::DiscardCardEvent(_) => todo!(),
             MaoEvent::DrawedCardEvent(_) => {
                 if player_index == self.player_turn {
                     self.update_turn(PlayerTurnChange::default());
@@ -1447,16 +2788,43 @@ impl MaoCore {
             }
             MaoEvent::GiveCardEvent { .. } => (),
             MaoEvent::StackPropertyRunsOut { .. } => (),
+            MaoEvent::StackRefilled { .. } => (),
+            MaoEvent::HandLimitExceeded { .. } => (),
+            MaoEvent::StackVisibilityChanged { .. } => (),
+            MaoEvent::TurnPassed { .. } => (),
+            MaoEvent::HandsSwapped { .. } => (),
+            MaoEvent::PenaltyApplied { .. } => (),
             MaoEvent::GameStart => (),
+            MaoEvent::PlayerJoined { .. } => (),
+            MaoEvent::RuleActivated { .. } => (),
+            MaoEvent::RuleDeactivated { .. } => (),
             MaoEvent::EndPlayerTurn { .. } => (),
             MaoEvent::VerifyEvent => unreachable!("verify event"),
             MaoEvent::PlayerPenality { .. } => (),
-            MaoEvent::SayEvent { .. } => todo!(),
-            MaoEvent::PhysicalEvent { .. } => todo!(),
+            // saying or doing something never ends a turn
+            MaoEvent::SayEvent { .. } => (),
+            MaoEvent::PhysicalEvent { .. } => (),
         }
         Ok(())
     }
 
+    /// Runs `event` through the whole pipeline: the rules are called, their results are propagated
+    /// and the penalities are given
+    ///
+    /// The player of the event is its [`MaoEvent::actor`], the current player if it has none
+    ///
+    /// Returns all the wrong interactions returned by the rules
+    pub fn dispatch_event(
+        &mut self,
+        event: MaoEvent,
+    ) -> Result<Vec<WrongPlayerInteraction>, Error> {
+        let player_index = event.actor().unwrap_or(self.player_turn);
+        let res = self.on_event(&event)?;
+        let wrong_int = self.propagate_on_event_results_and_execute(player_index, &event, &res)?;
+        self.penalize_wrong_interactions(player_index, &wrong_int)?;
+        Ok(wrong_int)
+    }
+
     /// Call [`Rule::on_event`] on each activated rules
     /// Returns all the results of the activated rules
     ///
@@ -1470,14 +2838,25 @@ impl MaoCore {
     pub fn on_event(&mut self, event: &MaoEvent) -> Result<Vec<MaoEventResult>, Error> {
         if event.is_recordable() {
             self.player_events.push(event.to_owned());
+            self.last_draw = match event {
+                MaoEvent::DrawedCardEvent(card_event) => {
+                    Some((card_event.to_owned(), self.player_turn, self.turn))
+                }
+                _ => None,
+            };
         }
         let mut results = Vec::with_capacity(self.activated_rules.len());
         for i in 0..self.activated_rules.len() {
-            results.push(self
+            let rule_index = self.activated_rules[i];
+            let res = self
                 .available_rules
-                .get(self.activated_rules[i])
+                .get(rule_index)
                 .unwrap()
-                .get_on_event_func()(event, self)?);
+                .get_on_event_func()(event, self)?;
+            if !matches!(res.res_type, MaoEventResultType::Ignored) {
+                *self.rule_fire_counts.entry(rule_index).or_default() += 1;
+            }
+            results.push(res);
         }
         Ok(results)
     }
@@ -1515,6 +2894,8 @@ impl MaoCore {
                     }
                     MaoEventResultType::ForgetSomething(s) => wrong_interactions
                         .push(WrongPlayerInteraction::ForgotSomething(s.to_owned())),
+                    MaoEventResultType::GivePenality(give) => wrong_interactions
+                        .push(WrongPlayerInteraction::GivePenality(give.to_owned())),
                     MaoEventResultType::Ignored => (),
                     MaoEventResultType::OverrideBasicRule(_)
                     | MaoEventResultType::ExecuteBeforeTurnChange(_)
@@ -1533,7 +2914,8 @@ impl MaoCore {
                     // cannot disallow the action of a rule
                     MaoEventResultType::Ignored
                     | MaoEventResultType::Disallow(_)
-                    | MaoEventResultType::ForgetSomething(_) => (),
+                    | MaoEventResultType::ForgetSomething(_)
+                    | MaoEventResultType::GivePenality(_) => (),
                     MaoEventResultType::OverrideBasicRule(_)
                     | MaoEventResultType::ExecuteBeforeTurnChange(_)
                     | MaoEventResultType::ExecuteAfterTurnChange(_) => not_ignored.push(res),
@@ -1588,10 +2970,71 @@ impl MaoCore {
         Ok(())
     }
 
+    /// Checks that the player `player_index` does not have more cards than `max_hand_size` of the [`Config`]
+    ///
+    /// If so, [`MaoEvent::HandLimitExceeded`] is released and if all rules ignore it,
+    /// the last cards of the player are moved to the default discard stack
+    ///
+    /// # Errors
+    ///
+    /// fails if `player_index` is out of range or if there is no discardable stack,
+    /// in the latter case the player keeps all its cards
+    pub fn check_hand_limit(&mut self, player_index: usize) -> Result<(), Error> {
+        let Some(max_hand_size) = self.config.max_hand_size else {
+            return Ok(());
+        };
+        if self.get_player_hand_len(player_index)? <= max_hand_size {
+            return Ok(());
+        }
+        let event = MaoEvent::HandLimitExceeded { player_index };
+        if self
+            .on_event(&event)?
+            .iter()
+            .any(|r| !matches!(r.res_type, MaoEventResultType::Ignored))
+        {
+            return Ok(());
+        }
+        let stack_index = self
+            .default_discard_target()
+            .ok_or(Error::NoStackAvailable {
+                stacks: vec![StackType::Discardable],
+            })?;
+        let excess: Vec<Card> = self.players[player_index]
+            .get_cards_mut()
+            .drain(max_hand_size..)
+            .collect();
+        self.stacks[stack_index].get_cards_mut().extend(excess);
+        Ok(())
+    }
+
+    /// Adds `cards` to the hand of the player `player_index` and checks its hand limit
+    /// (see [`Self::check_hand_limit`])
+    ///
+    /// # Errors
+    ///
+    /// fails if `player_index` is out of range or if the cards in excess cannot be discarded,
+    /// in the latter case `cards` stay in the hand of the player
+    fn add_cards_to_hand(
+        &mut self,
+        player_index: usize,
+        cards: impl IntoIterator<Item = Card>,
+    ) -> Result<(), Error> {
+        let len = self.players.len();
+        self.players
+            .get_mut(player_index)
+            .ok_or(Error::InvalidPlayerIndex { player_index, len })?
+            .get_cards_mut()
+            .extend(cards);
+        self.check_hand_limit(player_index)
+    }
+
     /// if `stack_index` is None, the first drawable stack will be gotten
     ///
     /// this function does not edit the length of stacks
     ///
+    /// [`MaoEvent::StackRefilled`] is released once the stack is refilled, the refills without
+    /// checking the rules only release it if some cards have been moved
+    ///
     /// # Error
     ///
     /// fails if stack_index is out if range or if there is no drawable stack available
@@ -1624,13 +3067,9 @@ impl MaoCore {
 
         let mut stacks_spe =
             self.get_specific_stacks_mut(&[StackType::Playable, StackType::Discardable]);
-        let mut cards = Vec::with_capacity(
-            stacks_spe
-                .iter()
-                .map(|(_, stack)| stack.get_cards().len())
-                .sum(),
-        );
-        // foreach add to cards and clear stacks
+        let mut cards = Vec::with_capacity(stacks_spe.iter().map(|(_, stack)| stack.len()).sum());
+        // foreach add to cards and clear stacks, each stack is visited once whatever its types
+        // and a playable one keeps its top card even if it is discardable too
         for i in 0..stacks_spe.len() {
             let (_, stack) = stacks_spe.get_mut(i).unwrap();
             if stack.get_stack_types().contains(&StackType::Playable) {
@@ -1650,6 +3089,86 @@ impl MaoCore {
             .get_mut(stack_index)
             .ok_or(Error::InvalidStackIndex { stack_index, len })?;
         stack.get_cards_mut().extend_from_slice(&cards);
+        if check_rules || !cards.is_empty() {
+            self.on_event(&MaoEvent::StackRefilled {
+                stack_index,
+                cards_added: cards.len(),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Moves all the cards of the discardable stacks but their top one under the top card
+    /// of the playable stack `playable_stack_index` and releases a [`MaoEvent::StackRefilled`],
+    /// returns the number of moved cards
+    pub fn recycle_discard_to_playable(
+        &mut self,
+        playable_stack_index: usize,
+    ) -> Result<usize, Error> {
+        let stack = self
+            .stacks
+            .get(playable_stack_index)
+            .ok_or(Error::InvalidStackIndex {
+                stack_index: playable_stack_index,
+                len: self.stacks.len(),
+            })?;
+        if !stack.get_stack_types().contains(&StackType::Playable) {
+            return Err(Error::NoStackAvailable {
+                stacks: vec![StackType::Playable],
+            });
+        }
+        let mut cards = Vec::new();
+        for (_, stack) in self
+            .get_specific_stacks_mut(&[StackType::Discardable])
+            .into_iter()
+            .filter(|(i, _)| *i != playable_stack_index)
+        {
+            if let Some(top) = stack.pop() {
+                cards.append(stack.deref_mut());
+                stack.push(top);
+            }
+        }
+        let stack = self.stacks.get_mut(playable_stack_index).unwrap();
+        let top_index = stack.len().saturating_sub(1);
+        stack
+            .get_cards_mut()
+            .splice(top_index..top_index, cards.iter().cloned());
+        self.on_event(&MaoEvent::StackRefilled {
+            stack_index: playable_stack_index,
+            cards_added: cards.len(),
+        })?;
+        Ok(cards.len())
+    }
+
+    /// Returns true if [`Self::refill_drawable_stacks`] would bring back at least one [`Card`],
+    /// that is if a playable stack holds more than its top card or if a discardable stack is not empty
+    pub fn can_refill(&self) -> bool {
+        self.get_specific_stacks(&[StackType::Playable, StackType::Discardable])
+            .iter()
+            .any(|(_, stack)| {
+                if stack.get_stack_types().contains(&StackType::Playable) {
+                    stack.len() > 1
+                } else {
+                    !stack.is_empty()
+                }
+            })
+    }
+
+    /// Refills the first drawable stack if all the drawable stacks together hold less cards
+    /// than the `refill_threshold` of the [`Config`] and if there are cards to refill with
+    /// (see [`Self::can_refill`])
+    pub fn refill_if_below_threshold(&mut self) -> Result<(), Error> {
+        let Some(threshold) = self.config.refill_threshold else {
+            return Ok(());
+        };
+        let nb_cards: usize = self
+            .get_drawable_stacks()
+            .iter()
+            .map(|(_, stack)| stack.len())
+            .sum();
+        if nb_cards < threshold && self.can_refill() {
+            self.refill_drawable_stacks(None, true)?;
+        }
         Ok(())
     }
 
@@ -1690,30 +3209,46 @@ impl MaoCore {
 
     /// Updates the player turn to suit `changes`
     pub fn update_turn(&mut self, changes: PlayerTurnChange) {
+        (self.player_turn, self.turn) = self.compute_turn(&changes);
+    }
+
+    /// Returns the indexes of all players starting from the current one and following the turn direction
+    pub fn players_in_turn_order(&self) -> Vec<usize> {
+        let nb_players = self.players.len() as isize;
+        (0..nb_players)
+            .map(|i| (self.player_turn as isize + self.turn * i).rem_euclid(nb_players) as usize)
+            .collect()
+    }
+
+    /// Returns the player whose turn it would be after applying `change`,
+    /// without updating anything
+    pub fn peek_next_player(&self, change: &PlayerTurnChange) -> usize {
+        self.compute_turn(change).0
+    }
+
+    /// Returns the (player_turn, turn) resulting of `changes`
+    fn compute_turn(&self, changes: &PlayerTurnChange) -> (usize, isize) {
+        self.compute_turn_from((self.player_turn, self.turn), changes)
+    }
+
+    /// Returns the (player_turn, turn) resulting of `changes` applied on (`player_turn`, `turn`)
+    fn compute_turn_from(
+        &self,
+        (player_turn, turn): (usize, isize),
+        changes: &PlayerTurnChange,
+    ) -> (usize, isize) {
         let nb_players = self.players.len();
-        if let Some(step) = match changes {
-            PlayerTurnChange::Update(v) => match v {
-                PlayerTurnUpdater::Set(i) => {
-                    self.player_turn = i;
-                    None
-                }
-                PlayerTurnUpdater::Update(i) => Some(i),
-            },
-            PlayerTurnChange::Rotate(v) => match v {
-                PlayerTurnUpdater::Set(i) => {
-                    self.turn *= -1;
-                    self.player_turn = i;
-                    None
-                }
-                PlayerTurnUpdater::Update(i) => {
-                    self.turn *= -1;
-                    Some(i)
-                }
-            },
-        } {
-            self.player_turn = (self.player_turn as isize
-                + (self.turn * step) % (nb_players as isize))
-                .rem_euclid(nb_players as isize) as usize;
+        let (updater, turn) = match changes {
+            PlayerTurnChange::Update(v) => (v, turn),
+            PlayerTurnChange::Rotate(v) => (v, -turn),
+        };
+        match updater {
+            PlayerTurnUpdater::Set(i) => (*i, turn),
+            PlayerTurnUpdater::Update(step) => (
+                (player_turn as isize + (turn * step) % (nb_players as isize))
+                    .rem_euclid(nb_players as isize) as usize,
+                turn,
+            ),
         }
     }
 
@@ -1816,15 +3351,13 @@ impl MaoCore {
                 self.update_turn(PlayerTurnChange::Update(PlayerTurnUpdater::Update(1)));
             }
             // all rules have ignored the event
-            self.players
-                .get_mut(card_event.player_index)
-                .unwrap()
-                .get_cards_mut()
-                .push(card.to_owned());
+            self.add_cards_to_hand(card_event.player_index, [card.to_owned()])?;
+            self.refill_if_below_threshold()?;
         } else {
             let mut values: Vec<&MaoEventResult> = Vec::new();
             // push back the card into the stack (been removed before)
             self.stacks.get_mut(stack_index).unwrap().push(card);
+            self.last_draw = None;
             for result in &res {
                 if !matches!(&result.res_type, MaoEventResultType::Ignored) {
                     values.push(result);
@@ -1848,6 +3381,81 @@ impl MaoCore {
 
 // players' actions
 impl MaoCore {
+    /// Gives back the card of the last draw to its stack and reverts the turn as it was before the draw
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`Error::NothingToUndo`] if the most recent recordable event is not a draw
+    pub fn undo_last_draw(&mut self) -> Result<(), Error> {
+        let (card_event, player_turn, turn) = self.last_draw.take().ok_or(Error::NothingToUndo)?;
+        let stack_index = card_event.stack_index.ok_or(Error::NothingToUndo)?;
+        if stack_index >= self.stacks.len() {
+            return Err(Error::InvalidStackIndex {
+                stack_index,
+                len: self.stacks.len(),
+            });
+        }
+        let hand = self
+            .players
+            .get_mut(card_event.player_index)
+            .ok_or(Error::NothingToUndo)?
+            .get_cards_mut();
+        if hand.last() != Some(&card_event.played_card) {
+            return Err(Error::NothingToUndo);
+        }
+        let card = hand.pop().unwrap();
+        self.stacks.get_mut(stack_index).unwrap().push(card);
+        if self.player_events.last() == Some(&MaoEvent::DrawedCardEvent(card_event)) {
+            self.player_events.pop();
+        }
+        self.player_turn = player_turn;
+        self.turn = turn;
+        Ok(())
+    }
+
+    /// Shuffles the hand of the player `player_index` with the random generator of the game
+    pub fn shuffle_player_hand(&mut self, player_index: usize) -> Result<(), Error> {
+        let len = self.players.len();
+        let player = self
+            .players
+            .get_mut(player_index)
+            .ok_or(Error::InvalidPlayerIndex { player_index, len })?;
+        player.get_cards_mut().shuffle(&mut self.rng);
+        Ok(())
+    }
+
+    /// Gives the whole hand of each player to the player `direction` places after it
+    pub fn rotate_hands(&mut self, direction: isize) {
+        let nb_players = self.players.len() as isize;
+        let hands: Vec<Vec<Card>> = self
+            .players
+            .iter_mut()
+            .map(|player| std::mem::take(player.get_cards_mut()))
+            .collect();
+        for (i, hand) in hands.into_iter().enumerate() {
+            let target = (i as isize + direction).rem_euclid(nb_players) as usize;
+            *self.players[target].get_cards_mut() = hand;
+        }
+    }
+
+    /// Exchanges the whole hands of the players `a` and `b`
+    /// and notifies the rules with [`MaoEvent::HandsSwapped`]
+    pub fn swap_hands(&mut self, a: usize, b: usize) -> Result<(), Error> {
+        let len = self.players.len();
+        for player_index in [a, b] {
+            if player_index >= len {
+                return Err(Error::InvalidPlayerIndex { player_index, len });
+            }
+        }
+        if a != b {
+            let hand_a = std::mem::take(self.players[a].get_cards_mut());
+            let hand_b = std::mem::replace(self.players[b].get_cards_mut(), hand_a);
+            *self.players[a].get_cards_mut() = hand_b;
+        }
+        self.on_event(&MaoEvent::HandsSwapped { a, b })?;
+        Ok(())
+    }
+
     pub fn generate_common_draw() -> Vec<Card> {
         let types = &[
             CommonCardType::Spade,
@@ -1869,3 +3477,13 @@ impl MaoCore {
         cards
     }
 }
+
+impl DataContainer for MaoCore {
+    fn get_data(&self) -> &Data {
+        &self.game_data
+    }
+
+    fn get_data_mut(&mut self) -> &mut Data {
+        &mut self.game_data
+    }
+}
@@ -29,12 +29,36 @@ pub enum MaoEvent {
     },
     /// Released when a [`StackPropery`] runs out of [`Card`]s
     StackPropertyRunsOut { empty_stack_index: StackTarget },
+    /// Released when a drawable [`Stack`] has been refilled with the other stacks' [`Card`]s
+    StackRefilled {
+        stack_index: usize,
+        cards_added: usize,
+    },
+    /// Released when a player passes its turn
+    TurnPassed { player_index: usize },
+    /// Released when the players `a` and `b` have exchanged their hands
+    HandsSwapped { a: usize, b: usize },
+    /// Released when a [`Stack`] is turned face up or face down
+    StackVisibilityChanged { stack_index: usize, visible: bool },
+    /// Released when a player has more [`Card`]s than the maximum hand size after drawing
+    HandLimitExceeded { player_index: usize },
     /// Released when game starts
     GameStart,
+    /// Released when a player joins the game
+    PlayerJoined { player_index: usize },
+    /// Released before a rule is activated, the activation is refused if a rule disallows it
+    RuleActivated { rule_name: String },
+    /// Released when a rule has been deactivated
+    RuleDeactivated { rule_name: String },
     /// Released when a player has finished its turn
     EndPlayerTurn { events: Vec<MaoEvent> },
     /// Released when a player is going to take a penality
     PlayerPenality { player_target: usize },
+    /// Released when a player has drawn the cards of its penality
+    PenaltyApplied {
+        player_index: usize,
+        cards_drawn: usize,
+    },
     /// Released when verifying the validity of the rules (only called at inialization)
     VerifyEvent,
     /// Released when a player says something
@@ -54,15 +78,58 @@ impl MaoEvent {
         !matches!(
             self,
             MaoEvent::GameStart
+                | MaoEvent::PlayerJoined { .. }
+                | MaoEvent::RuleActivated { .. }
+                | MaoEvent::RuleDeactivated { .. }
                 | MaoEvent::VerifyEvent
                 | MaoEvent::StackPropertyRunsOut { .. }
+                | MaoEvent::StackRefilled { .. }
+                | MaoEvent::HandLimitExceeded { .. }
+                | MaoEvent::StackVisibilityChanged { .. }
+                | MaoEvent::HandsSwapped { .. }
                 | MaoEvent::EndPlayerTurn { .. }
                 | MaoEvent::PlayerPenality { .. }
+                | MaoEvent::PenaltyApplied { .. }
         )
     }
 
     pub fn can_change_turn(&self) -> bool {
-        matches!(self, Self::PlayedCardEvent(_) | Self::DrawedCardEvent(_))
+        matches!(
+            self,
+            Self::PlayedCardEvent(_)
+                | Self::DiscardCardEvent(_)
+                | Self::DrawedCardEvent(_)
+                | Self::TurnPassed { .. }
+        )
+    }
+
+    /// Returns the index of the player who performed the event, [`None`] if the event
+    /// has not been triggered by a player action
+    pub fn actor(&self) -> Option<usize> {
+        match self {
+            MaoEvent::PlayedCardEvent(card_event)
+            | MaoEvent::DiscardCardEvent(card_event)
+            | MaoEvent::DrawedCardEvent(card_event) => Some(card_event.player_index),
+            MaoEvent::GiveCardEvent {
+                from_player_index, ..
+            } => Some(*from_player_index),
+            MaoEvent::SayEvent { player_index, .. }
+            | MaoEvent::PhysicalEvent { player_index, .. }
+            | MaoEvent::TurnPassed { player_index } => Some(*player_index),
+            MaoEvent::StackPropertyRunsOut { .. }
+            | MaoEvent::StackRefilled { .. }
+            | MaoEvent::StackVisibilityChanged { .. }
+            | MaoEvent::HandLimitExceeded { .. }
+            | MaoEvent::HandsSwapped { .. }
+            | MaoEvent::GameStart
+            | MaoEvent::PlayerJoined { .. }
+            | MaoEvent::RuleActivated { .. }
+            | MaoEvent::RuleDeactivated { .. }
+            | MaoEvent::EndPlayerTurn { .. }
+            | MaoEvent::PlayerPenality { .. }
+            | MaoEvent::PenaltyApplied { .. }
+            | MaoEvent::VerifyEvent => None,
+        }
     }
 
     /// Returns the concerned [`Card`] of the event if the event is about a card event
@@ -73,10 +140,19 @@ impl MaoEvent {
             MaoEvent::DrawedCardEvent(ref e) => Some(&e.played_card),
             MaoEvent::GiveCardEvent { card, .. } => Some(card),
             MaoEvent::StackPropertyRunsOut { .. } => None,
+            MaoEvent::StackRefilled { .. } => None,
+            MaoEvent::HandLimitExceeded { .. } => None,
+            MaoEvent::StackVisibilityChanged { .. } => None,
+            MaoEvent::TurnPassed { .. } => None,
+            MaoEvent::HandsSwapped { .. } => None,
             MaoEvent::GameStart => None,
+            MaoEvent::PlayerJoined { .. } => None,
+            MaoEvent::RuleActivated { .. } => None,
+            MaoEvent::RuleDeactivated { .. } => None,
             MaoEvent::EndPlayerTurn { .. } => None,
             MaoEvent::VerifyEvent => unreachable!("verify event"),
             MaoEvent::PlayerPenality { .. } => None,
+            MaoEvent::PenaltyApplied { .. } => None,
             MaoEvent::SayEvent { .. } => None,
             MaoEvent::PhysicalEvent { .. } => None,
         }
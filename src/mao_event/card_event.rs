@@ -1,4 +1,6 @@
-use crate::card::Card;
+use crate::{
+    card::Card, error::Error, mao::mao_core::MaoCore, stack::stack_property::StackProperty,
+};
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
 pub struct CardEvent {
@@ -22,4 +24,44 @@ impl CardEvent {
             card_index,
         }
     }
+
+    /// Checks that the indexes of this event exist inside `mao`
+    /// and that the player holds `played_card` at `card_index`
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the player, the card inside its hand
+    /// or the stack (if any) does not exist and [`Error::CardMismatch`] if the card
+    /// at `card_index` is not `played_card`
+    pub fn validate(&self, mao: &MaoCore) -> Result<(), Error> {
+        let player = mao
+            .players()
+            .get(self.player_index)
+            .ok_or(Error::InvalidPlayerIndex {
+                player_index: self.player_index,
+                len: mao.players().len(),
+            })?;
+        let Some(card) = player.get_cards().get(self.card_index) else {
+            return Err(Error::InvalidCardIndex {
+                card_index: self.card_index,
+                len: player.len(),
+            });
+        };
+        if !card.same_card(&self.played_card) {
+            return Err(Error::CardMismatch {
+                card_index: self.card_index,
+                expected: Box::new(card.to_owned()),
+                received: Box::new(self.played_card.to_owned()),
+            });
+        }
+        if let Some(stack_index) = self.stack_index {
+            if stack_index >= mao.stacks().len() {
+                return Err(Error::InvalidStackIndex {
+                    stack_index,
+                    len: mao.stacks().len(),
+                });
+            }
+        }
+        Ok(())
+    }
 }
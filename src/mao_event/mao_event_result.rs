@@ -62,6 +62,25 @@ impl std::fmt::Display for ForgotSomething {
     }
 }
 
+/// A penality given by a rule to a player which is not necessarily the one who made the action
+#[derive(Clone, Debug)]
+pub struct GivePenality {
+    pub rule: String,
+    pub msg: String,
+    pub player_index: usize,
+    pub penality: Option<PenalityCallbackFunction>,
+}
+
+impl std::fmt::Display for GivePenality {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} (player {})",
+            self.rule, self.msg, self.player_index
+        )
+    }
+}
+
 impl PartialEq for Disallow {
     fn eq(&self, other: &Self) -> bool {
         self.rule == other.rule && self.msg == other.msg
@@ -113,6 +132,8 @@ pub enum WrongPlayerInteraction {
     Disallow(Disallow),
     /// The player forgot to do/say/... something
     ForgotSomething(ForgotSomething),
+    /// A rule gives a penality to a player
+    GivePenality(GivePenality),
 }
 
 impl WrongPlayerInteraction {
@@ -161,6 +182,7 @@ impl std::fmt::Display for WrongPlayerInteraction {
             match self {
                 WrongPlayerInteraction::Disallow(dis) => dis.to_string(),
                 WrongPlayerInteraction::ForgotSomething(f) => f.to_string(),
+                WrongPlayerInteraction::GivePenality(g) => g.to_string(),
             }
         )
     }
@@ -174,6 +196,8 @@ pub enum MaoEventResultType {
     Disallow(Disallow),
     /// The player forgot to do/say/... something
     ForgetSomething(ForgotSomething),
+    /// A player (not necessarily the one who made the action) has to take a penality
+    GivePenality(GivePenality),
     /// The event will override basic rules, the function should modify itself the player turn, the basics rules won't be modified besides
     OverrideBasicRule(CallbackFunction),
     /// Contains a function which will modified the player turn but not override the basic change turn, this function will be executed before the basic change turn
@@ -1,19 +1,43 @@
-use crate::{card::Card, stack::stack_property::StackProperty};
+use serde::{Deserialize, Serialize};
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
+use crate::{
+    card::Card,
+    data::{Data, DataContainer},
+    stack::stack_property::StackProperty,
+};
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
 pub struct Player {
     pseudo: String,
     hand: Vec<Card>, // (rule name, card)
+    #[serde(default)]
+    player_data: Data,
+    /// the score accumulated over the rounds
+    #[serde(default)]
+    score: isize,
 }
 
 impl Player {
     pub fn new(pseudo: String, hand: Vec<Card>) -> Self {
-        Self { pseudo, hand }
+        Self {
+            pseudo,
+            hand,
+            player_data: Data::new(),
+            score: 0,
+        }
     }
 
     pub fn get_pseudo(&self) -> &str {
         &self.pseudo
     }
+
+    pub fn score(&self) -> isize {
+        self.score
+    }
+
+    pub fn add_score(&mut self, points: isize) {
+        self.score += points;
+    }
 }
 
 impl StackProperty for Player {
@@ -25,3 +49,13 @@ impl StackProperty for Player {
         &mut self.hand
     }
 }
+
+impl DataContainer for Player {
+    fn get_data(&self) -> &Data {
+        &self.player_data
+    }
+
+    fn get_data_mut(&mut self) -> &mut Data {
+        &mut self.player_data
+    }
+}
@@ -22,8 +22,19 @@ pub struct Library {
     author: fn() -> Option<&'static str>,
 }
 
+/// Where the functions of a [`Rule`] come from
+enum RuleLib {
+    Library(Container<Library>),
+    /// a rule written inside this crate so that the tests do not need a compiled library
+    #[cfg(test)]
+    Function {
+        name: &'static str,
+        on_event: OnEventFunctionSignature,
+    },
+}
+
 pub struct Rule {
-    lib: Container<Library>,
+    lib: RuleLib,
     light_filename: String,
     path: PathBuf,
 }
@@ -31,7 +42,7 @@ pub struct Rule {
 impl Rule {
     pub fn new(lib: Container<Library>, name: String) -> Self {
         Self {
-            lib,
+            lib: RuleLib::Library(lib),
             path: PathBuf::from(&name),
             light_filename: PathBuf::from(name)
                 .file_name()
@@ -47,16 +58,38 @@ impl Rule {
         }
     }
 
+    /// Creates a rule named `name` calling `on_event`, its light filename is `lib{name}`
+    #[cfg(test)]
+    pub(crate) fn from_fn(name: &'static str, on_event: OnEventFunctionSignature) -> Self {
+        Self {
+            lib: RuleLib::Function { name, on_event },
+            light_filename: format!("lib{name}"),
+            path: PathBuf::from(format!("lib{name}.so")),
+        }
+    }
+
     pub fn get_on_event_func(&self) -> OnEventFunctionSignature {
-        self.lib.on_event
+        match &self.lib {
+            RuleLib::Library(lib) => lib.on_event,
+            #[cfg(test)]
+            RuleLib::Function { on_event, .. } => *on_event,
+        }
     }
 
     pub(crate) fn get_version(&self) -> String {
-        (self.lib.get_version)()
+        match &self.lib {
+            RuleLib::Library(lib) => (lib.get_version)(),
+            #[cfg(test)]
+            RuleLib::Function { .. } => VERSION.to_owned(),
+        }
     }
 
     pub(crate) fn get_actions(&self) -> Option<Vec<Vec<NodeState>>> {
-        self.lib.get_actions()
+        match &self.lib {
+            RuleLib::Library(lib) => lib.get_actions(),
+            #[cfg(test)]
+            RuleLib::Function { .. } => None,
+        }
     }
 
     pub fn is_valid_rule(&self, mao: &mut MaoCore) -> Result<(), Error> {
@@ -74,15 +107,27 @@ impl Rule {
     }
 
     pub fn description(&self) -> Option<&'static str> {
-        self.lib.description()
+        match &self.lib {
+            RuleLib::Library(lib) => lib.description(),
+            #[cfg(test)]
+            RuleLib::Function { .. } => None,
+        }
     }
 
     pub fn name(&self) -> &'static str {
-        self.lib.name()
+        match &self.lib {
+            RuleLib::Library(lib) => lib.name(),
+            #[cfg(test)]
+            RuleLib::Function { name, .. } => name,
+        }
     }
 
     pub fn author(&self) -> Option<&'static str> {
-        self.lib.author()
+        match &self.lib {
+            RuleLib::Library(lib) => lib.author(),
+            #[cfg(test)]
+            RuleLib::Function { .. } => None,
+        }
     }
 }
 
@@ -94,6 +139,10 @@ impl ToOwned for Rule {
     type Owned = Self;
 
     fn to_owned(&self) -> Self::Owned {
+        #[cfg(test)]
+        if let RuleLib::Function { name, on_event } = self.lib {
+            return Self::from_fn(name, on_event);
+        }
         Self::try_from(self.path.to_str().unwrap()).unwrap()
     }
 }
@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 use crate::card::Card;
 
 use self::{stack_property::StackProperty, stack_type::StackType};
@@ -5,12 +7,13 @@ use self::{stack_property::StackProperty, stack_type::StackType};
 pub mod stack_property;
 pub mod stack_type;
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Stack {
     cards: Vec<Card>,
     visible: bool,
     stack_type: Vec<StackType>,
     #[allow(dead_code)]
+    #[serde(default)]
     in_fron_of: Option<String>, // player pseudo
 }
 
@@ -51,6 +54,22 @@ impl Stack {
         &mut self.stack_type
     }
 
+    /// Adds `stack_type` to the types of this stack, returns false if it already had it
+    pub fn add_type(&mut self, stack_type: StackType) -> bool {
+        if self.stack_type.contains(&stack_type) {
+            return false;
+        }
+        self.stack_type.push(stack_type);
+        true
+    }
+
+    /// Removes `stack_type` from the types of this stack, returns false if it did not have it
+    pub fn remove_type(&mut self, stack_type: &StackType) -> bool {
+        let len = self.stack_type.len();
+        self.stack_type.retain(|t| t != stack_type);
+        len != self.stack_type.len()
+    }
+
     pub fn top(&self) -> Option<&Card> {
         self.cards.last()
     }
@@ -13,7 +13,27 @@ pub trait StackProperty: std::fmt::Debug {
             Ok(self.get_cards_mut().remove(card_index))
         }
     }
+    /// Removes and returns the first [`Card`] matching `pred`
+    fn remove_first_matching(&mut self, pred: &dyn Fn(&Card) -> bool) -> Option<Card> {
+        let card_index = self.get_cards().iter().position(pred)?;
+        Some(self.get_cards_mut().remove(card_index))
+    }
+    /// Replaces the [`Card`] at `card_index` by `card` and returns the replaced one
+    fn replace_card(&mut self, card_index: usize, card: Card) -> Result<Card, Error> {
+        let len = self.get_cards().len();
+        match self.get_cards_mut().get_mut(card_index) {
+            Some(old) => Ok(std::mem::replace(old, card)),
+            None => Err(Error::InvalidCardIndex { card_index, len }),
+        }
+    }
     fn add_card(&mut self, card: Card) {
         self.get_cards_mut().push(card)
     }
+    /// Returns the number of [`Card`]s
+    fn len(&self) -> usize {
+        self.get_cards().len()
+    }
+    fn is_empty(&self) -> bool {
+        self.get_cards().is_empty()
+    }
 }
@@ -1,7 +1,10 @@
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Deserialize)]
 pub enum StackType {
-    Playable,    // where we play
-    Drawable,    //pioche
+    Playable, // where we play
+    #[serde(alias = "Drawble")]
+    Drawable, //pioche
     Discardable, // defausse
 }
 
@@ -12,7 +15,7 @@ impl std::fmt::Display for StackType {
             "{}",
             match self {
                 StackType::Playable => "Playable",
-                StackType::Drawable => "Drawble",
+                StackType::Drawable => "Drawable",
                 StackType::Discardable => "Discardable",
             }
         )